@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dual_pivot::dual_pivot_quick_sort_by,
+    element::SortOrder,
+    heap_sort::heap_sort_by,
+    insertion_sort::{insertion_sort_by, shell_sort_by},
+    merge_sort::merge_sort_by,
+    pivot::PivotStrategy,
+    quick_sort::{
+        intro_sort_by, iterative_quick_sort_by, quick_select_by, quick_sort_by, PartitionScheme,
+    },
+    radix_sort::{radix_sort, RadixKey},
+    trace::Trace,
+};
+
+/// The sorting algorithm of a run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Partitions around a pivot and sorts both sides recursively
+    #[default]
+    QuickSort,
+    /// Yaroslavskiy's quicksort with two pivots and three parts
+    DualPivotQuickSort,
+    /// Quicksort with an explicit stack of pending ranges instead of recursion
+    IterativeQuickSort,
+    /// Quicksort which falls back to heap sort when the recursion gets too
+    /// deep and to insertion sort for small ranges
+    IntroSort,
+    /// Partitions like quicksort, but continues only on the side which holds
+    /// the k-th smallest element
+    QuickSelect,
+    /// Sorts both halves recursively and merges them
+    MergeSort,
+    /// Builds a max-heap and repeatedly moves its root to the end
+    HeapSort,
+    /// Inserts every element into the sorted prefix before it
+    InsertionSort,
+    /// Insertion sort over shrinking gaps
+    ShellSort,
+    /// Least significant digit first, in base 10
+    RadixSort,
+}
+
+choice!(Algorithm, "algorithm", {
+    QuickSort => "quick-sort", "Quicksort",
+    DualPivotQuickSort => "dual-pivot-quick-sort", "Dual-pivot quicksort",
+    IterativeQuickSort => "iterative-quick-sort", "Iterative quicksort",
+    IntroSort => "intro-sort", "Introsort",
+    QuickSelect => "quick-select", "Quickselect",
+    MergeSort => "merge-sort", "Merge sort",
+    HeapSort => "heap-sort", "Heap sort",
+    InsertionSort => "insertion-sort", "Insertion sort",
+    ShellSort => "shell-sort", "Shell sort",
+    RadixSort => "radix-sort", "Radix sort",
+});
+
+impl Algorithm {
+    /// Whether the partition scheme and pivot strategy apply
+    pub fn uses_pivot(self) -> bool {
+        matches!(
+            self,
+            Self::QuickSort | Self::IterativeQuickSort | Self::IntroSort | Self::QuickSelect
+        )
+    }
+}
+
+/// The options of a single sort run
+///
+/// The partition scheme and the pivot only affect the quicksorts and
+/// quickselect, the other options only the algorithm they are named after.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SortOptions {
+    pub algorithm: Algorithm,
+    pub scheme: PartitionScheme,
+    pub pivot: PivotStrategy,
+    /// The seed of all random decisions
+    pub seed: u64,
+    /// The recursion depth at which introsort switches to heap sort, or 0
+    /// for 2·⌊log₂ n⌋
+    pub depth_limit: usize,
+    /// The length up to which introsort switches to insertion sort
+    pub cutoff: usize,
+    /// Whether iterative quicksort sorts the smaller side of a partition
+    /// first, which keeps at most ⌊log₂ n⌋ + 1 ranges on its stack
+    pub smaller_first: bool,
+    /// The index in sorted order of the element quickselect looks for, 0 for
+    /// the smallest
+    pub k: usize,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::default(),
+            scheme: PartitionScheme::default(),
+            pivot: PivotStrategy::default(),
+            seed: 0,
+            depth_limit: 0,
+            cutoff: 4,
+            smaller_first: true,
+            k: 0,
+        }
+    }
+}
+
+/// Sorts the whole `array` with the algorithm of `options` and traces every
+/// step
+///
+/// Quickselect only moves the k-th element into place instead of sorting.
+///
+/// Fails for radix sort if the elements have no integer key, and for
+/// quickselect if k is outside of the array.
+pub fn sort<T: Ord + Clone + RadixKey>(
+    array: &mut [T],
+    options: SortOptions,
+    order: SortOrder,
+) -> Result<Trace<T>, String> {
+    let right = array.len() as isize - 1;
+    let compare = |a: &T, b: &T| order.compare(a, b);
+    Ok(match options.algorithm {
+        Algorithm::QuickSort => quick_sort_by(array, 0, right, options, compare),
+        Algorithm::IterativeQuickSort => iterative_quick_sort_by(array, 0, right, options, compare),
+        Algorithm::IntroSort => intro_sort_by(array, 0, right, options, compare),
+        Algorithm::QuickSelect => {
+            if !array.is_empty() && options.k >= array.len() {
+                return Err(format!(
+                    "k = {} is outside of the array of {} elements",
+                    options.k,
+                    array.len()
+                ));
+            }
+            quick_select_by(array, 0, right, options.k as isize, options, compare)
+        }
+        Algorithm::DualPivotQuickSort => dual_pivot_quick_sort_by(array, 0, right, compare),
+        Algorithm::MergeSort => merge_sort_by(array, 0, right, compare),
+        Algorithm::HeapSort => heap_sort_by(array, 0, right, compare),
+        Algorithm::InsertionSort => insertion_sort_by(array, 0, right, compare),
+        Algorithm::ShellSort => shell_sort_by(array, 0, right, compare),
+        Algorithm::RadixSort => radix_sort(array, 0, right, order)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{choice::Choice, testing::ARRAYS, trace::Step};
+
+    #[test]
+    fn records_only_swaps_which_move_something() {
+        for &algorithm in Algorithm::ALL {
+            let options = SortOptions {
+                algorithm,
+                ..SortOptions::default()
+            };
+            for input in ARRAYS {
+                let mut array = input.to_vec();
+                let trace = sort(&mut array, options, SortOrder::Ascending).unwrap();
+                let self_swap = trace
+                    .events()
+                    .iter()
+                    .any(|event| matches!(event.step, Step::Swap { a, b } if a == b));
+                assert!(!self_swap, "{algorithm} {input:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::Display;
+
+use leptos::*;
+use quicksort::{
+    element::Element,
+    trace::{Step, Trace},
+};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+/// Bars are only labeled up to this many elements
+const MAX_LABELS: usize = 40;
+
+/// A numeric value which determines the height of a bar
+pub trait Magnitude {
+    fn magnitude(&self) -> Option<f64>;
+}
+
+impl Magnitude for Element {
+    fn magnitude(&self) -> Option<f64> {
+        match self {
+            Self::Integer(value) => Some(*value as f64),
+            Self::Float(value) if value.0.is_finite() => Some(value.0),
+            _ => None,
+        }
+    }
+}
+
+/// The heights of all elements in `0.0..=1.0`
+///
+/// Elements without a [`Magnitude`] are scaled by their rank instead.
+fn heights<T: Magnitude + Ord>(array: &[T]) -> Vec<f64> {
+    if let Some(values) = array.iter().map(T::magnitude).collect::<Option<Vec<_>>>() {
+        let min = values.iter().copied().fold(0.0, f64::min);
+        let max = values.iter().copied().fold(min, f64::max);
+        let range = max - min;
+        return values
+            .into_iter()
+            .map(|value| {
+                if range > 0.0 {
+                    (value - min) / range
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+    }
+    let mut sorted: Vec<&T> = array.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    array
+        .iter()
+        .map(|element| {
+            let rank = sorted.binary_search(&element).unwrap_or(0);
+            (rank + 1) as f64 / sorted.len() as f64
+        })
+        .collect()
+}
+
+/// Draws the array as bars which slide into place when they are swapped
+#[component]
+pub fn BarChart<T>(
+    #[prop(into)] trace: Signal<Trace<T>>,
+    /// The amount of events which already happened
+    step: RwSignal<usize>,
+) -> impl IntoView
+where
+    T: Magnitude + Ord + Clone + Display + 'static,
+{
+    let len = move || trace.with(|trace| trace.input.len());
+    let bar_width = move || WIDTH / len().max(1) as f64;
+    let heights = create_memo(move |_| trace.with(|trace| heights(&trace.input)));
+    let labels = create_memo(move |_| {
+        trace.with(|trace| trace.input.iter().map(T::to_string).collect::<Vec<_>>())
+    });
+    // the position of every input element
+    let positions = create_memo(move |_| {
+        trace.with(|trace| {
+            let origins = trace.origins_at(step());
+            let mut positions = vec![0; origins.len()];
+            for (position, origin) in origins.into_iter().enumerate() {
+                positions[origin] = position;
+            }
+            positions
+        })
+    });
+    let last = move || {
+        trace.with(|trace| {
+            let step = step().min(trace.events().len());
+            step.checked_sub(1).map(|last| trace.events()[last].clone())
+        })
+    };
+    let touched = create_memo(move |_| match last().map(|event| event.step) {
+        Some(Step::Compare { index, .. }) => vec![index as usize],
+        Some(Step::Swap { a, b } | Step::CompareElements { a, b, .. }) => {
+            vec![a as usize, b as usize]
+        }
+        _ => Vec::new(),
+    });
+    // the range of the frame which is currently running
+    let window = create_memo(move |_| {
+        let event = last()?;
+        trace.with(|trace| {
+            let frame = trace.root.find(event.frame)?;
+            Some((frame.left, frame.right))
+        })
+    });
+    let position = move |id: usize| positions.with(|positions| positions.get(id).copied());
+    let bar = move |id: usize| {
+        let height =
+            move || heights.with(|heights| heights.get(id).copied().unwrap_or(0.0)) * HEIGHT;
+        let is_touched = move || {
+            position(id).is_some_and(|position| touched.with(|touched| touched.contains(&position)))
+        };
+        view! {
+            <g
+                class="bar-slot"
+                style:transform=move || {
+                    format!("translateX({}px)", position(id).unwrap_or(0) as f64 * bar_width())
+                }
+            >
+                <rect
+                    x=1
+                    y=move || HEIGHT - height().max(2.0) + 10.0
+                    width=move || (bar_width() - 2.0).max(1.0)
+                    height=move || height().max(2.0)
+                    class="bar"
+                    class:touched=is_touched
+                />
+                <Show when=move || len() <= MAX_LABELS>
+                    <text x=move || bar_width() / 2.0 y=(HEIGHT + 30.0) class="bar-label anchor-middle">
+                        {move || labels.with(|labels| labels.get(id).cloned())}
+                    </text>
+                </Show>
+            </g>
+        }
+    };
+    view! {
+        <svg viewBox=format!("0 0 {WIDTH} {}", HEIGHT + 50.0)>
+            {move || window().filter(|(left, right)| left <= right).map(|(left, right)| {
+                view! {
+                    <rect
+                        x=(left as f64 * bar_width())
+                        y=0
+                        width=((right - left + 1) as f64 * bar_width())
+                        height=(HEIGHT + 15.0)
+                        class="window"
+                    />
+                }
+            })}
+            <For each=move || 0..len() key=|&id| id children=bar/>
+        </svg>
+    }
+}
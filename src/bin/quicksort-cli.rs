@@ -0,0 +1,200 @@
+use std::{env, fmt, fs, process::ExitCode};
+
+use quicksort::{
+    algorithm::{sort, Algorithm, SortOptions},
+    choice,
+    choice::Choice,
+    element::{ElementKind, SortOrder},
+    input::parse_array,
+    pivot::PivotStrategy,
+    quick_sort::PartitionScheme,
+    render::{Backend, Density, Layout, Renderer, StaticSvgBackend, TextBackend},
+    trace::Trace,
+    trace_file::TraceFile,
+};
+
+/// What is written for a trace
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Format {
+    /// One line per call
+    #[default]
+    Text,
+    /// A trace file with the frames and events
+    Json,
+    /// A standalone SVG document of the list view
+    Svg,
+}
+
+choice!(Format, "format", {
+    Text => "text", "Plain text",
+    Json => "json", "JSON trace file",
+    Svg => "svg", "SVG document",
+});
+
+/// The parsed command line
+#[derive(Debug, Default)]
+struct Args {
+    input: String,
+    kind: ElementKind,
+    order: SortOrder,
+    options: SortOptions,
+    format: Format,
+    density: Density,
+    hide_base_cases: bool,
+    /// The file to write to instead of stdout
+    output: Option<String>,
+    help: bool,
+}
+
+/// Joins the names of all values of an option
+fn names<T: fmt::Display>(all: impl IntoIterator<Item = T>) -> String {
+    all.into_iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn usage() -> String {
+    format!(
+        "\
+Usage: quicksort-cli [OPTIONS] <ARRAY>...
+
+Sorts ARRAY with the chosen algorithm, or selects from it, and prints the trace.
+The elements may be separated by commas or spaces.
+
+Options:
+  --algorithm <NAME>   {}
+  --type <TYPE>        {}
+  --order <ORDER>      {}
+  --scheme <SCHEME>    {} (quicksorts and quick-select only)
+  --pivot <PIVOT>      {} (quicksorts and quick-select only)
+  --seed <SEED>        the seed of the random pivot
+  --depth-limit <N>    the depth at which intro-sort switches to heap sort,
+                       0 for 2·log₂ n (default)
+  --cutoff <N>         the length up to which intro-sort switches to
+                       insertion sort (default 4)
+  --no-smaller-first   makes iterative-quick-sort sort the left side first
+                       instead of the smaller one
+  -k <K>               the index in sorted order quick-select looks for,
+                       0 for the smallest (default)
+  --format <FORMAT>    {}
+  --density <DENSITY>  {} (svg only)
+  --hide-base-cases    leaves out calls which do no work
+  -o, --output <FILE>  writes to FILE instead of stdout
+  -h, --help           prints this help",
+        names(Algorithm::ALL),
+        names(ElementKind::ALL),
+        names(SortOrder::ALL),
+        names(PartitionScheme::ALL),
+        names(PivotStrategy::ALL),
+        names(Format::ALL),
+        names(Density::ALL),
+    )
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut array = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for `{arg}`"))
+            };
+            match arg.as_str() {
+                "--algorithm" => parsed.options.algorithm = value()?.parse()?,
+                "--type" => parsed.kind = value()?.parse()?,
+                "--order" => parsed.order = value()?.parse()?,
+                "--scheme" => parsed.options.scheme = value()?.parse()?,
+                "--pivot" => parsed.options.pivot = value()?.parse()?,
+                "--seed" => {
+                    let seed = value()?;
+                    parsed.options.seed =
+                        seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?;
+                }
+                "--depth-limit" => {
+                    let depth_limit = value()?;
+                    parsed.options.depth_limit = depth_limit
+                        .parse()
+                        .map_err(|_| format!("invalid depth limit `{depth_limit}`"))?;
+                }
+                "--cutoff" => {
+                    let cutoff = value()?;
+                    parsed.options.cutoff = cutoff
+                        .parse()
+                        .map_err(|_| format!("invalid cutoff `{cutoff}`"))?;
+                }
+                "-k" => {
+                    let k = value()?;
+                    parsed.options.k = k.parse().map_err(|_| format!("invalid k `{k}`"))?;
+                }
+                "--no-smaller-first" => parsed.options.smaller_first = false,
+                "--format" => parsed.format = value()?.parse()?,
+                "--density" => parsed.density = value()?.parse()?,
+                "--hide-base-cases" => parsed.hide_base_cases = true,
+                "-o" | "--output" => parsed.output = Some(value()?),
+                "-h" | "--help" => parsed.help = true,
+                _ if arg.starts_with("--") => return Err(format!("unknown option `{arg}`")),
+                _ => array.push(arg),
+            }
+        }
+        parsed.input = array.join(" ");
+        Ok(parsed)
+    }
+}
+
+fn render<B: Backend>(trace: &Trace<impl fmt::Display>, args: &Args, backend: B) -> B::Output {
+    let mut renderer = Renderer::new(trace, trace.events().len(), backend);
+    renderer.hide_base_cases = args.hide_base_cases;
+    renderer.render(&trace.root);
+    renderer.finish()
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let mut array = parse_array(&args.input, args.kind).map_err(|error| error.to_string())?;
+    let trace = sort(&mut array, args.options, args.order)?;
+    let output = match args.format {
+        Format::Text => render(&trace, args, TextBackend::new()),
+        Format::Json => {
+            let mut json = TraceFile::new(args.kind, args.order, args.options, trace).to_json();
+            json.push('\n');
+            json
+        }
+        Format::Svg => render(
+            &trace,
+            args,
+            StaticSvgBackend::new(Layout::new(args.density)),
+        ),
+    };
+    match &args.output {
+        Some(path) => {
+            fs::write(path, output).map_err(|error| format!("cannot write `{path}`: {error}"))
+        }
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{}", usage());
+            return ExitCode::from(2);
+        }
+    };
+    if args.help {
+        println!("{}", usage());
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/// An option with a fixed set of named values
+pub trait Choice: Copy + PartialEq + std::fmt::Display + std::str::FromStr + 'static {
+    /// Every value in the order it is offered
+    const ALL: &'static [Self];
+
+    /// The identifier used in option values, URLs and arguments
+    fn name(self) -> &'static str;
+
+    /// The human readable name
+    fn label(self) -> &'static str;
+}
+
+/// Implements [`Choice`] for a field-less enum, along with `Display` and
+/// `FromStr` by its names
+///
+/// `$what` names the option in the error of an unknown name.
+#[macro_export]
+macro_rules! choice {
+    ($type:ty, $what:literal, { $($variant:ident => $name:literal, $label:literal,)* }) => {
+        impl $crate::choice::Choice for $type {
+            const ALL: &'static [Self] = &[$(Self::$variant),*];
+
+            fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            fn label(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $label,)*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $type {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str($crate::choice::Choice::name(*self))
+            }
+        }
+
+        impl ::std::str::FromStr for $type {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <Self as $crate::choice::Choice>::ALL
+                    .iter()
+                    .copied()
+                    .find(|choice| $crate::choice::Choice::name(*choice) == s)
+                    .ok_or_else(|| format!(concat!("unknown ", $what, " `{}`"), s))
+            }
+        }
+    };
+}
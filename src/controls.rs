@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use leptos::*;
+use quicksort::{
+    algorithm::{Algorithm, SortOptions},
+    choice::Choice,
+    generate::{Distribution, Generator, MAX_LEN},
+};
+
+/// A labeled `<select>` for a [`Choice`]
+#[component]
+pub fn ChoiceSelect<T: Choice>(
+    label: &'static str,
+    #[prop(into)] value: Signal<T>,
+    #[prop(into)] on_change: Callback<T>,
+) -> impl IntoView {
+    view! {
+        <label>
+            {label}" "
+            <select on:change=move |ev| {
+                if let Ok(choice) = event_target_value(&ev).parse() {
+                    on_change(choice);
+                }
+            }>
+                {T::ALL.iter().map(|&choice| view! {
+                    <option value=choice.name() selected=move || value() == choice>{choice.label()}</option>
+                }).collect_view()}
+            </select>
+        </label>
+    }
+}
+
+/// A labeled number `<input>`
+#[component]
+pub fn NumberInput<T>(
+    label: &'static str,
+    #[prop(into)] value: Signal<T>,
+    #[prop(into)] on_change: Callback<T>,
+    #[prop(default = 0)] min: u64,
+    #[prop(optional)] max: Option<u64>,
+) -> impl IntoView
+where
+    T: ToString + FromStr + 'static,
+{
+    view! {
+        <label>
+            {label}" "
+            <input
+                type="number"
+                min=min
+                max=max
+                prop:value=move || value.with(T::to_string)
+                on:change=move |ev| {
+                    if let Ok(number) = event_target_value(&ev).parse() {
+                        on_change(number);
+                    }
+                }
+            />
+        </label>
+    }
+}
+
+/// Generates input arrays from a [`Distribution`]
+#[component]
+pub fn GeneratorPanel(
+    /// Called with every generated array
+    #[prop(into)]
+    on_generate: Callback<Vec<i64>>,
+) -> impl IntoView {
+    let generator = create_rw_signal(Generator::default());
+    let distribution = move || generator().distribution;
+    view! {
+        <div class="controls">
+            <ChoiceSelect
+                label="Distribution"
+                value=Signal::derive(distribution)
+                on_change=move |distribution| generator.update(|generator| generator.distribution = distribution)
+            />
+            <NumberInput
+                label="Length"
+                min=1
+                max=MAX_LEN as u64
+                value=Signal::derive(move || generator().len)
+                on_change=move |len: usize| generator.update(|generator| generator.len = len.clamp(1, MAX_LEN))
+            />
+            <NumberInput
+                label="Seed"
+                value=Signal::derive(move || generator().seed)
+                on_change=move |seed| generator.update(|generator| generator.seed = seed)
+            />
+            <Show when=move || distribution() == Distribution::NearlySorted>
+                <NumberInput
+                    label="Swaps"
+                    max=(MAX_LEN * MAX_LEN) as u64
+                    value=Signal::derive(move || generator().swaps)
+                    on_change=move |swaps: usize| generator.update(|generator| generator.swaps = swaps.min(generator.max_swaps()))
+                />
+            </Show>
+            <Show when=move || distribution() == Distribution::FewUnique>
+                <NumberInput
+                    label="Distinct values"
+                    min=1
+                    value=Signal::derive(move || generator().unique)
+                    on_change=move |unique| generator.update(|generator| generator.unique = unique)
+                />
+            </Show>
+            <button on:click=move |_| on_generate(generator().generate())>"Generate"</button>
+        </div>
+    }
+}
+
+/// Edits the [`SortOptions`] of one sort run
+#[component]
+pub fn SortOptionsControls(
+    options: RwSignal<SortOptions>,
+    /// Names the run when several are shown
+    #[prop(optional, into)]
+    title: MaybeProp<&'static str>,
+    /// The length of the array, which picks the median for quickselect
+    #[prop(into)]
+    len: Signal<usize>,
+    /// Additional controls at the end of the row
+    #[prop(optional)]
+    children: Option<Children>,
+) -> impl IntoView {
+    view! {
+        <div class="controls">
+            {move || title.get().map(|title| view! { <strong>{title}</strong> })}
+            <ChoiceSelect
+                label="Algorithm"
+                value=Signal::derive(move || options().algorithm)
+                on_change=move |algorithm| options.update(|options| options.algorithm = algorithm)
+            />
+            <Show when=move || options().algorithm.uses_pivot()>
+                <ChoiceSelect
+                    label="Partition scheme"
+                    value=Signal::derive(move || options().scheme)
+                    on_change=move |scheme| options.update(|options| options.scheme = scheme)
+                />
+                <ChoiceSelect
+                    label="Pivot"
+                    value=Signal::derive(move || options().pivot)
+                    on_change=move |pivot| options.update(|options| options.pivot = pivot)
+                />
+                <NumberInput
+                    label="Seed"
+                    value=Signal::derive(move || options().seed)
+                    on_change=move |seed| options.update(|options| options.seed = seed)
+                />
+            </Show>
+            <Show when=move || options().algorithm == Algorithm::IterativeQuickSort>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || options().smaller_first
+                        on:change=move |ev| {
+                            let smaller_first = event_target_checked(&ev);
+                            options.update(|options| options.smaller_first = smaller_first);
+                        }
+                    />
+                    "Smaller side first"
+                </label>
+            </Show>
+            <Show when=move || options().algorithm == Algorithm::QuickSelect>
+                <NumberInput
+                    label="k (0 = smallest)"
+                    value=Signal::derive(move || options().k)
+                    on_change=move |k| options.update(|options| options.k = k)
+                />
+                <button on:click=move |_| {
+                    let median = len().saturating_sub(1) / 2;
+                    options.update(|options| options.k = median);
+                }>"Median"</button>
+            </Show>
+            <Show when=move || options().algorithm == Algorithm::IntroSort>
+                <NumberInput
+                    label="Depth limit (0 = 2·log₂ n)"
+                    value=Signal::derive(move || options().depth_limit)
+                    on_change=move |depth_limit| options.update(|options| options.depth_limit = depth_limit)
+                />
+                <NumberInput
+                    label="Cutoff"
+                    value=Signal::derive(move || options().cutoff)
+                    on_change=move |cutoff| options.update(|options| options.cutoff = cutoff)
+                />
+            </Show>
+            {children.map(|children| children())}
+        </div>
+    }
+}
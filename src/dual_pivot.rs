@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+
+use crate::{
+    trace::{Step, Trace, Tracer},
+    Frame, FrameKind,
+};
+
+struct DualPivotQuickSort<F> {
+    compare: F,
+    tracer: Tracer,
+}
+
+impl<F> DualPivotQuickSort<F> {
+    /// Yaroslavskiy's partitioning with the first and the last element as
+    /// pivots
+    fn sort<T>(&mut self, array: &mut [T], left: isize, right: isize) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let entered = self.tracer.enter(left, right);
+        let label = format!("dqS({left}, {right}, ...)");
+        if right <= left {
+            let work = entered.start..entered.start + 1;
+            let settled = vec![left..right + 1];
+            return self
+                .tracer
+                .exit(entered, label, work, settled, FrameKind::Base, Vec::new());
+        }
+        let tracer = &mut self.tracer;
+        let compare = &mut self.compare;
+        tracer.push(Step::ChoosePivot { index: left });
+        tracer.push(Step::ChoosePivot { index: right });
+        // the smaller pivot goes first
+        if tracer.compare_elements(array, right, left, compare) == Ordering::Less {
+            tracer.swap(array, left, right);
+        }
+        let mut lt = left + 1;
+        let mut gt = right - 1;
+        let mut i = left + 1;
+        while i <= gt {
+            if tracer.compare_elements(array, i, left, compare) == Ordering::Less {
+                tracer.swap(array, lt, i);
+                lt += 1;
+                i += 1;
+                tracer.push(Step::Advance { index: i });
+            } else if tracer.compare_elements(array, right, i, compare) == Ordering::Less {
+                tracer.swap(array, i, gt);
+                gt -= 1;
+                tracer.push(Step::Retreat { index: gt });
+            } else {
+                i += 1;
+                tracer.push(Step::Advance { index: i });
+            }
+        }
+        lt -= 1;
+        gt += 1;
+        tracer.swap(array, left, lt);
+        tracer.swap(array, right, gt);
+        tracer.push(Step::PlacePivot { range: lt..lt + 1 });
+        tracer.push(Step::PlacePivot { range: gt..gt + 1 });
+        let work = entered.start..tracer.events.len();
+        let children = vec![
+            self.sort(array, left, lt - 1),
+            self.sort(array, lt + 1, gt - 1),
+            self.sort(array, gt + 1, right),
+        ];
+        let settled = vec![lt..lt + 1, gt..gt + 1];
+        let kind = FrameKind::DualPartition { pivots: [lt, gt] };
+        self.tracer
+            .exit(entered, label, work, settled, kind, children)
+    }
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+pub fn dual_pivot_quick_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut quick_sort = DualPivotQuickSort {
+        compare,
+        tracer: Tracer::default(),
+    };
+    let input = array.to_vec();
+    let root = quick_sort.sort(array, left, right);
+    Trace::new(input, root, quick_sort.tracer.events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_sorts, ARRAYS};
+
+    #[test]
+    fn dual_pivot_quick_sort_sorts() {
+        assert_sorts("dual-pivot quicksort", |array, order| {
+            let right = array.len() as isize - 1;
+            dual_pivot_quick_sort_by(array, 0, right, |a, b| order.compare(a, b))
+        });
+    }
+
+    /// Checks that both pivots of `frame` and its children hold their final
+    /// values once the partition is done
+    fn assert_pivots_placed(trace: &Trace<i64>, frame: &Frame, sorted: &[i64]) {
+        if let FrameKind::DualPartition { pivots } = frame.kind {
+            let array = trace.array_at(frame.work.end);
+            for pivot in pivots {
+                assert_eq!(array[pivot as usize], sorted[pivot as usize], "{frame:?}");
+            }
+        }
+        for child in &frame.children {
+            assert_pivots_placed(trace, child, sorted);
+        }
+    }
+
+    #[test]
+    fn places_the_pivots() {
+        for input in ARRAYS {
+            let mut array = input.to_vec();
+            let trace = dual_pivot_quick_sort_by(&mut array, 0, input.len() as isize - 1, i64::cmp);
+            assert_pivots_placed(&trace, &trace.root, &array);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::{cmp::Ordering, fmt};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// A `f64` which is totally ordered by [`f64::total_cmp`]
+///
+/// JSON has no numbers for `NaN` and the infinities, so those are serialized
+/// as the strings `"NaN"`, `"inf"` and `"-inf"` and read back by
+/// [`Element::coerce`].
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct TotalF64(pub f64);
+
+impl Serialize for TotalF64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() {
+            serializer.serialize_f64(self.0)
+        } else {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+}
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl fmt::Display for TotalF64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The type of the elements of an input array
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ElementKind {
+    #[default]
+    Integer,
+    Float,
+    Char,
+    String,
+    /// A pair of integers, ordered lexicographically
+    Pair,
+}
+
+choice!(ElementKind, "element type", {
+    Integer => "integer", "Integers",
+    Float => "float", "Floats",
+    Char => "char", "Characters",
+    String => "string", "Strings",
+    Pair => "pair", "Pairs",
+});
+
+/// An element of an input array
+///
+/// All elements of one array are of the same [`ElementKind`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Element {
+    Integer(i64),
+    Float(TotalF64),
+    Char(char),
+    String(String),
+    Pair(i64, i64),
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => value.fmt(f),
+            Self::Float(value) => value.fmt(f),
+            Self::Char(value) => value.fmt(f),
+            Self::String(value) => value.fmt(f),
+            Self::Pair(a, b) => write!(f, "({a},{b})"),
+        }
+    }
+}
+
+impl Element {
+    /// Converts the element to `kind` where this loses nothing
+    ///
+    /// Formats like JSON do not distinguish `3` from `3.0` or `"a"` as a
+    /// character from `"a"` as a string, so those are widened to `kind`.
+    /// Floats which are not finite are read from their names.
+    pub fn coerce(self, kind: ElementKind) -> Option<Self> {
+        match (self, kind) {
+            (Self::Integer(value), ElementKind::Float) => Some(Self::Float(TotalF64(value as f64))),
+            (Self::String(value), ElementKind::Float) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| !value.is_finite())
+                .map(|value| Self::Float(TotalF64(value))),
+            (Self::Char(value), ElementKind::String) => Some(Self::String(value.to_string())),
+            (element, kind) if element.kind() == kind => Some(element),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> ElementKind {
+        match self {
+            Self::Integer(_) => ElementKind::Integer,
+            Self::Float(_) => ElementKind::Float,
+            Self::Char(_) => ElementKind::Char,
+            Self::String(_) => ElementKind::String,
+            Self::Pair(..) => ElementKind::Pair,
+        }
+    }
+}
+
+/// The order the elements are sorted in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+choice!(SortOrder, "sort order", {
+    Ascending => "ascending", "Ascending",
+    Descending => "descending", "Descending",
+});
+
+impl SortOrder {
+    /// Compares two elements so that sorting by it yields this order
+    pub fn compare<T: Ord>(self, a: &T, b: &T) -> Ordering {
+        match self {
+            Self::Ascending => a.cmp(b),
+            Self::Descending => b.cmp(a),
+        }
+    }
+}
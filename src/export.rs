@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use leptos::{
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    *,
+};
+use quicksort::trace_file::TraceFile;
+use web_sys::{
+    js_sys::Array, Blob, BlobPropertyBag, CanvasRenderingContext2d, Element, File, FileReader,
+    HtmlAnchorElement, HtmlCanvasElement, HtmlImageElement, HtmlInputElement, Url, XmlSerializer,
+};
+
+use crate::controls::NumberInput;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// How long a downloaded blob URL is kept before it is revoked
+const REVOKE_DELAY: Duration = Duration::from_secs(10);
+
+/// A standalone copy of a drawn `<svg>`
+pub struct Standalone {
+    /// The serialized document with the stylesheet inlined
+    pub source: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Standalone {
+    /// Copies `svg` and inlines style.css, so the file renders without the page
+    pub fn new(svg: &Element) -> Result<Self, JsValue> {
+        let (width, height) = size(svg);
+        let svg: Element = svg.clone_node_with_deep(true)?.unchecked_into();
+        let style = document().create_element_ns(Some(SVG_NAMESPACE), "style")?;
+        style.set_text_content(Some(include_str!("style.css")));
+        svg.prepend_with_node_1(&style)?;
+        svg.set_attribute("xmlns", SVG_NAMESPACE)?;
+        svg.set_attribute("width", &width.to_string())?;
+        svg.set_attribute("height", &height.to_string())?;
+        let source = XmlSerializer::new()?.serialize_to_string(&svg)?;
+        Ok(Self {
+            source,
+            width,
+            height,
+        })
+    }
+
+    pub fn blob(&self) -> Result<Blob, JsValue> {
+        text_blob(&self.source, "image/svg+xml")
+    }
+}
+
+fn text_blob(text: &str, mime: &str) -> Result<Blob, JsValue> {
+    let parts = Array::of1(&JsValue::from_str(text));
+    Blob::new_with_str_sequence_and_options(&parts, BlobPropertyBag::new().type_(mime))
+}
+
+/// The size of the `viewBox` of `svg`, or its size on screen without one
+fn size(svg: &Element) -> (f64, f64) {
+    let view_box = svg.get_attribute("viewBox").unwrap_or_default();
+    let numbers = view_box
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<f64>, _>>();
+    match numbers.as_deref() {
+        Ok([_, _, width, height]) => (*width, *height),
+        _ => {
+            let rect = svg.get_bounding_client_rect();
+            (rect.width(), rect.height())
+        }
+    }
+}
+
+/// Lets the browser save `url` as `filename`
+fn download(url: &str, filename: &str) -> Result<(), JsValue> {
+    let anchor: HtmlAnchorElement = document().create_element("a")?.unchecked_into();
+    anchor.set_href(url);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+/// Lets the browser save `blob` as `filename`
+///
+/// The download starts after `click()` returns, so the URL is revoked a
+/// while later rather than right away.
+fn download_blob(blob: &Blob, filename: &str) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+    let result = download(&url, filename);
+    set_timeout(move || _ = Url::revoke_object_url(&url), REVOKE_DELAY);
+    result
+}
+
+/// Downloads `svg` as a standalone .svg file
+pub fn export_svg(svg: &Element, filename: &str) -> Result<(), JsValue> {
+    download_blob(&Standalone::new(svg)?.blob()?, filename)
+}
+
+/// Downloads `file` as a .json file
+pub fn export_trace(file: &TraceFile, filename: &str) -> Result<(), JsValue> {
+    download_blob(&text_blob(&file.to_json(), "application/json")?, filename)
+}
+
+/// Reads the text of `file` and passes it to `on_load`
+pub fn read_text(
+    file: &File,
+    on_load: impl FnOnce(Result<String, JsValue>) + 'static,
+) -> Result<(), JsValue> {
+    let reader = FileReader::new()?;
+    let loaded = reader.clone();
+    let onload = Closure::once_into_js(move || {
+        on_load(loaded.result().and_then(|text| {
+            text.as_string()
+                .ok_or_else(|| JsValue::from_str("the file is not text"))
+        }))
+    });
+    reader.set_onload(Some(onload.unchecked_ref()));
+    reader.read_as_text(file)
+}
+
+/// Downloads `svg` as a .png file, rasterized on an off-screen canvas
+///
+/// The image has to load before it can be drawn, so errors after that point
+/// are passed to `on_error`.
+pub fn export_png(
+    svg: &Element,
+    scale: f64,
+    filename: String,
+    on_error: impl FnOnce(JsValue) + 'static,
+) -> Result<(), JsValue> {
+    let standalone = Standalone::new(svg)?;
+    let url = Url::create_object_url_with_blob(&standalone.blob()?)?;
+    let image = HtmlImageElement::new()?;
+    let loaded = image.clone();
+    let src = url.clone();
+    let onload = Closure::once_into_js(move || {
+        let result = rasterize(&loaded, standalone.width, standalone.height, scale)
+            .and_then(|png| download(&png, &filename));
+        if let Err(error) = result.and(Url::revoke_object_url(&url)) {
+            on_error(error);
+        }
+    });
+    image.set_onload(Some(onload.unchecked_ref()));
+    image.set_src(&src);
+    Ok(())
+}
+
+/// Draws `image` on a white canvas and returns it as a PNG data URL
+fn rasterize(
+    image: &HtmlImageElement,
+    width: f64,
+    height: f64,
+    scale: f64,
+) -> Result<String, JsValue> {
+    let canvas: HtmlCanvasElement = document().create_element("canvas")?.unchecked_into();
+    canvas.set_width((width * scale).ceil() as u32);
+    canvas.set_height((height * scale).ceil() as u32);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("the canvas has no 2d context"))?
+        .unchecked_into();
+    context.set_fill_style(&JsValue::from_str("white"));
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+    context.scale(scale, scale)?;
+    context.draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, width, height)?;
+    canvas.to_data_url_with_type("image/png")
+}
+
+/// A readable message of an error thrown by the browser
+fn message(error: JsValue) -> String {
+    error
+        .dyn_ref::<web_sys::js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| format!("{error:?}"))
+}
+
+/// The name of the exported file of the `index`th of `count` drawings
+///
+/// Comparisons export one file per pane, named after its letter.
+fn filename(index: usize, count: usize, extension: &str) -> String {
+    if count == 1 {
+        format!("quicksort.{extension}")
+    } else {
+        format!("quicksort-{}.{extension}", char::from(b'a' + index as u8))
+    }
+}
+
+/// Buttons which download every `<svg>` inside of `target`
+#[component]
+pub fn ExportPanel(
+    target: NodeRef<html::Div>,
+    /// Whether the current view has no `<svg>` to export
+    #[prop(into)]
+    disabled: Signal<bool>,
+) -> impl IntoView {
+    let scale = create_rw_signal(2u32);
+    let (error, set_error) = create_signal(None::<String>);
+    // one drawing per pane of a comparison
+    let svgs = move || {
+        let Some(list) = target
+            .get_untracked()
+            .and_then(|target| target.query_selector_all("svg").ok())
+        else {
+            return Vec::new();
+        };
+        (0..list.length())
+            .filter_map(|index| list.item(index)?.dyn_into::<Element>().ok())
+            .collect::<Vec<_>>()
+    };
+    let on_svg = move |_| {
+        let svgs = svgs();
+        let result = svgs
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, svg)| export_svg(svg, &filename(index, svgs.len(), "svg")));
+        set_error(result.err().map(message));
+    };
+    let on_png = move |_| {
+        let svgs = svgs();
+        let result = svgs.iter().enumerate().try_for_each(|(index, svg)| {
+            export_png(
+                svg,
+                scale.get_untracked() as f64,
+                filename(index, svgs.len(), "png"),
+                move |error| set_error(Some(message(error))),
+            )
+        });
+        set_error(result.err().map(message));
+    };
+    view! {
+        <div class="controls">
+            <button on:click=on_svg disabled=disabled>"Export SVG"</button>
+            <button on:click=on_png disabled=disabled>"Export PNG"</button>
+            <NumberInput label="PNG scale" value=scale on_change=move |value| scale.set(value) min=1 max=8/>
+            {move || error().map(|error| view! { <span class="error">{error}</span> })}
+        </div>
+    }
+}
+
+/// Saves the current trace as JSON and loads saved traces
+#[component]
+pub fn TraceFilePanel(
+    /// The trace which is saved, only read when saving
+    #[prop(into)]
+    file: Signal<TraceFile>,
+    #[prop(into)] on_load: Callback<TraceFile>,
+) -> impl IntoView {
+    let (error, set_error) = create_signal(None::<String>);
+    let on_export = move |_| {
+        let result = file.with_untracked(|file| export_trace(file, "quicksort.json"));
+        set_error(result.err().map(message));
+    };
+    let on_change = move |ev: ev::Event| {
+        let input: HtmlInputElement = event_target(&ev);
+        let Some(selected) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        // allows loading the same file again after editing it
+        input.set_value("");
+        let result = read_text(&selected, move |text| {
+            match text
+                .map_err(message)
+                .and_then(|text| TraceFile::from_json(&text).map_err(|error| error.to_string()))
+            {
+                Ok(file) => {
+                    set_error(None);
+                    on_load(file);
+                }
+                Err(error) => set_error(Some(error)),
+            }
+        });
+        set_error(result.err().map(message));
+    };
+    view! {
+        <div class="controls">
+            <button on:click=on_export>"Export trace"</button>
+            <label class="file-button">
+                "Load trace"
+                <input type="file" accept=".json,application/json" on:change=on_change/>
+            </label>
+            {move || error().map(|error| view! { <span class="error">{error}</span> })}
+        </div>
+    }
+}
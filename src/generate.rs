@@ -0,0 +1,133 @@
+use crate::rng::Rng;
+
+/// A distribution of generated input arrays
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Distribution {
+    #[default]
+    Uniform,
+    Sorted,
+    Reversed,
+    /// Sorted, followed by a few random swaps
+    NearlySorted,
+    /// Uniformly random, but only with a few distinct values
+    FewUnique,
+    AllEqual,
+    /// Ascending up to the middle and descending afterwards
+    OrganPipe,
+    /// Several ascending runs
+    Sawtooth,
+}
+
+choice!(Distribution, "distribution", {
+    Uniform => "uniform", "Uniform random",
+    Sorted => "sorted", "Sorted",
+    Reversed => "reversed", "Reverse sorted",
+    NearlySorted => "nearly-sorted", "Nearly sorted",
+    FewUnique => "few-unique", "Few unique",
+    AllEqual => "all-equal", "All equal",
+    OrganPipe => "organ-pipe", "Organ pipe",
+    Sawtooth => "sawtooth", "Sawtooth",
+});
+
+/// The longest array the generator creates
+pub const MAX_LEN: usize = 1000;
+
+/// The options of the input generator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Generator {
+    pub distribution: Distribution,
+    pub len: usize,
+    pub seed: u64,
+    /// The amount of swaps of [`Distribution::NearlySorted`], of which at most
+    /// [`Generator::max_swaps`] are done
+    pub swaps: usize,
+    /// The amount of distinct values of [`Distribution::FewUnique`]
+    pub unique: usize,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            distribution: Distribution::default(),
+            len: 10,
+            seed: 0,
+            swaps: 2,
+            unique: 3,
+        }
+    }
+}
+
+impl Generator {
+    /// The most swaps which are worth doing, as more do not shuffle any further
+    pub fn max_swaps(&self) -> usize {
+        self.len * self.len
+    }
+
+    /// Generates an array, always the same one for the same options
+    pub fn generate(&self) -> Vec<i64> {
+        let mut rng = Rng::new(self.seed);
+        let len = self.len as i64;
+        match self.distribution {
+            Distribution::Uniform => (0..len).map(|_| rng.below(100) as i64).collect(),
+            Distribution::Sorted => (1..=len).collect(),
+            Distribution::Reversed => (1..=len).rev().collect(),
+            Distribution::NearlySorted => {
+                let mut array: Vec<i64> = (1..=len).collect();
+                for _ in 0..self.swaps.min(self.max_swaps()) {
+                    let a = rng.below(self.len as u64) as usize;
+                    let b = rng.below(self.len as u64) as usize;
+                    array.swap(a, b);
+                }
+                array
+            }
+            Distribution::FewUnique => {
+                let unique = self.unique.max(1) as u64;
+                (0..len).map(|_| rng.below(unique) as i64 + 1).collect()
+            }
+            Distribution::AllEqual => {
+                let value = rng.below(100) as i64;
+                vec![value; self.len]
+            }
+            Distribution::OrganPipe => (0..len).map(|i| i.min(len - 1 - i) + 1).collect(),
+            Distribution::Sawtooth => {
+                let period = (len / 4).max(1);
+                (0..len).map(|i| i % period + 1).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choice::Choice;
+
+    #[test]
+    fn same_seed_same_array() {
+        for &distribution in Distribution::ALL {
+            for seed in [0, 1, 42] {
+                let generator = Generator {
+                    distribution,
+                    len: 50,
+                    seed,
+                    ..Generator::default()
+                };
+                let array = generator.generate();
+                assert_eq!(array.len(), 50, "{distribution}");
+                assert_eq!(array, generator.generate(), "{distribution} {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_the_swaps() {
+        let generator = Generator {
+            distribution: Distribution::NearlySorted,
+            swaps: usize::MAX,
+            ..Generator::default()
+        };
+        let mut array = generator.generate();
+        array.sort();
+        assert_eq!(array, (1..=10).collect::<Vec<_>>());
+    }
+}
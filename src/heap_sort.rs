@@ -0,0 +1,106 @@
+use std::cmp::Ordering;
+
+use crate::{
+    trace::{Trace, Tracer},
+    Frame,
+};
+
+/// Moves the element at `root` down the max-heap of `array[left..=end]`
+/// until none of its children is greater
+fn sift_down<T>(
+    array: &mut [T],
+    left: isize,
+    mut root: isize,
+    end: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) {
+    loop {
+        let mut child = left + 2 * (root - left) + 1;
+        if child > end {
+            return;
+        }
+        if child < end
+            && tracer.compare_elements(array, child, child + 1, compare) == Ordering::Less
+        {
+            child += 1;
+        }
+        if tracer.compare_elements(array, root, child, compare) != Ordering::Less {
+            return;
+        }
+        tracer.swap(array, root, child);
+        root = child;
+    }
+}
+
+/// Heap sorts `array[left..=right]`, which has at least two elements
+///
+/// Building the heap and every removal of its maximum are separate passes.
+pub fn heap_passes<T>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Vec<Frame> {
+    let mut passes = vec![tracer.pass(
+        left,
+        right,
+        format!("heapify({left}, {right})"),
+        Vec::new(),
+        |tracer| {
+            for root in (left..=left + (right - left - 1) / 2).rev() {
+                sift_down(array, left, root, right, tracer, compare);
+            }
+        },
+    )];
+    for end in (left + 1..=right).rev() {
+        // the last removal also leaves the smallest element in place
+        let settled = if end == left + 1 {
+            vec![left..end + 1]
+        } else {
+            vec![end..end + 1]
+        };
+        passes.push(tracer.pass(
+            left,
+            end,
+            format!("extract({left}, {end})"),
+            settled,
+            |tracer| {
+                tracer.swap(array, left, end);
+                sift_down(array, left, left, end - 1, tracer, compare);
+            },
+        ));
+    }
+    passes
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+pub fn heap_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    mut compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut tracer = Tracer::default();
+    let input = array.to_vec();
+    let label = format!("hS({left}, {right}, ...)");
+    let root = tracer.sequence(left, right, label, |tracer| {
+        heap_passes(array, left, right, tracer, &mut compare)
+    });
+    Trace::new(input, root, tracer.events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_sorts;
+
+    #[test]
+    fn heap_sort_sorts() {
+        assert_sorts("heap sort", |array, order| {
+            let right = array.len() as isize - 1;
+            heap_sort_by(array, 0, right, |a, b| order.compare(a, b))
+        });
+    }
+}
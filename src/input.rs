@@ -0,0 +1,148 @@
+use std::{fmt, num::IntErrorKind};
+
+use crate::element::{Element, ElementKind, TotalF64};
+
+/// An error while parsing a user supplied array
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contains no elements
+    Empty,
+    /// The element at `position` is not of the expected kind
+    Invalid {
+        position: usize,
+        token: String,
+        kind: ElementKind,
+    },
+    /// The element at `position` does not fit into an `i64`
+    Overflow { position: usize, token: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the array is empty"),
+            Self::Invalid {
+                position,
+                token,
+                kind,
+            } => {
+                let expected = match kind {
+                    ElementKind::Integer => "an integer",
+                    ElementKind::Float => "a number",
+                    ElementKind::Char => "a single character",
+                    ElementKind::String => "a string",
+                    ElementKind::Pair => "a pair like `(1,2)`",
+                };
+                write!(f, "element {position} (`{token}`) is not {expected}")
+            }
+            Self::Overflow { position, token } => write!(
+                f,
+                "element {position} (`{token}`) is outside of {}..={}",
+                i64::MIN,
+                i64::MAX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Splits the input at commas and whitespace, outside of parentheses if `nested`
+fn tokens(input: &str, nested: bool) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' if nested => depth += 1,
+            ')' if nested => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                tokens.push(&input[start..i]);
+                start = i + 1;
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                tokens.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    tokens.push(&input[start..]);
+    tokens.retain(|token| !token.is_empty());
+    tokens
+}
+
+fn parse_integer(token: &str, position: usize) -> Result<i64, ParseError> {
+    token
+        .trim()
+        .parse()
+        .map_err(|error: std::num::ParseIntError| {
+            let token = token.to_string();
+            match error.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    ParseError::Overflow { position, token }
+                }
+                _ => ParseError::Invalid {
+                    position,
+                    token,
+                    kind: ElementKind::Integer,
+                },
+            }
+        })
+}
+
+fn parse_element(token: &str, position: usize, kind: ElementKind) -> Result<Element, ParseError> {
+    let invalid = || ParseError::Invalid {
+        position,
+        token: token.to_string(),
+        kind,
+    };
+    match kind {
+        ElementKind::Integer => parse_integer(token, position).map(Element::Integer),
+        ElementKind::Float => token
+            .parse()
+            .map(|value| Element::Float(TotalF64(value)))
+            .map_err(|_| invalid()),
+        ElementKind::Char => {
+            let mut chars = token.chars();
+            match (chars.next(), chars.next()) {
+                (Some(value), None) => Ok(Element::Char(value)),
+                _ => Err(invalid()),
+            }
+        }
+        ElementKind::String => Ok(Element::String(token.to_string())),
+        ElementKind::Pair => {
+            let (a, b) = token
+                .strip_prefix('(')
+                .and_then(|token| token.strip_suffix(')'))
+                .and_then(|token| token.split_once(','))
+                .ok_or_else(invalid)?;
+            Ok(Element::Pair(
+                parse_integer(a, position)?,
+                parse_integer(b, position)?,
+            ))
+        }
+    }
+}
+
+/// Parses a comma and/or whitespace separated list of elements
+pub fn parse_array(input: &str, kind: ElementKind) -> Result<Vec<Element>, ParseError> {
+    let array = tokens(input, kind == ElementKind::Pair)
+        .into_iter()
+        .enumerate()
+        .map(|(position, token)| parse_element(token, position, kind))
+        .collect::<Result<Vec<_>, _>>()?;
+    if array.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    Ok(array)
+}
+
+/// Formats an array the way [`parse_array`] accepts it
+pub fn format_array<T: fmt::Display>(array: &[T]) -> String {
+    array
+        .iter()
+        .map(T::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
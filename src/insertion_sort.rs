@@ -0,0 +1,130 @@
+use std::cmp::Ordering;
+
+use crate::{
+    trace::{Trace, Tracer},
+    Frame,
+};
+
+/// Moves the element at `index` backwards in steps of `gap` while the
+/// element before it is greater
+fn insert<T>(
+    array: &mut [T],
+    left: isize,
+    mut index: isize,
+    gap: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) {
+    while index - gap >= left
+        && tracer.compare_elements(array, index - gap, index, compare) == Ordering::Greater
+    {
+        tracer.swap(array, index - gap, index);
+        index -= gap;
+    }
+}
+
+/// Insertion sorts `array[left..=right]`
+///
+/// Every insertion into the sorted prefix is a separate pass.
+pub fn insertion_passes<T>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Vec<Frame> {
+    (left + 1..=right)
+        .map(|index| {
+            let settled = if index == right {
+                vec![left..right + 1]
+            } else {
+                Vec::new()
+            };
+            tracer.pass(left, index, format!("insert({index})"), settled, |tracer| {
+                insert(array, left, index, 1, tracer, compare);
+            })
+        })
+        .collect()
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+pub fn insertion_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    mut compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut tracer = Tracer::default();
+    let input = array.to_vec();
+    let label = format!("iS({left}, {right}, ...)");
+    let root = tracer.sequence(left, right, label, |tracer| {
+        insertion_passes(array, left, right, tracer, &mut compare)
+    });
+    Trace::new(input, root, tracer.events)
+}
+
+/// The gaps `1, 4, 13, 40, ...` of Knuth's sequence up to the first one
+/// which reaches a third of `len`, largest first
+fn gaps(len: isize) -> Vec<isize> {
+    let mut gaps = vec![1];
+    while let Some(&gap) = gaps.last().filter(|&&gap| gap < len / 3) {
+        gaps.push(3 * gap + 1);
+    }
+    gaps.reverse();
+    gaps
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+///
+/// Every gap is a separate pass of insertion sort on the elements which
+/// are that far apart.
+pub fn shell_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    mut compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut tracer = Tracer::default();
+    let input = array.to_vec();
+    let label = format!("shS({left}, {right}, ...)");
+    let root = tracer.sequence(left, right, label, |tracer| {
+        gaps(right - left + 1)
+            .into_iter()
+            .map(|gap| {
+                let settled = if gap == 1 {
+                    vec![left..right + 1]
+                } else {
+                    Vec::new()
+                };
+                tracer.pass(left, right, format!("gap {gap}"), settled, |tracer| {
+                    for index in left + gap..=right {
+                        insert(array, left, index, gap, tracer, &mut compare);
+                    }
+                })
+            })
+            .collect()
+    });
+    Trace::new(input, root, tracer.events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_sorts;
+
+    #[test]
+    fn insertion_sort_sorts() {
+        assert_sorts("insertion sort", |array, order| {
+            let right = array.len() as isize - 1;
+            insertion_sort_by(array, 0, right, |a, b| order.compare(a, b))
+        });
+    }
+
+    #[test]
+    fn shell_sort_sorts() {
+        assert_sorts("shell sort", |array, order| {
+            let right = array.len() as isize - 1;
+            shell_sort_by(array, 0, right, |a, b| order.compare(a, b))
+        });
+    }
+}
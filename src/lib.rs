@@ -0,0 +1,188 @@
+// frames settle lists of ranges, which often hold a single one
+#![allow(clippy::single_range_in_vec_init)]
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+#[macro_use]
+pub mod choice;
+
+pub mod algorithm;
+pub mod dual_pivot;
+pub mod element;
+pub mod generate;
+pub mod heap_sort;
+pub mod input;
+pub mod insertion_sort;
+pub mod merge_sort;
+pub mod pivot;
+pub mod quick_sort;
+pub mod radix_sort;
+pub mod render;
+pub mod rng;
+#[cfg(test)]
+mod testing;
+pub mod trace;
+pub mod trace_file;
+
+/// A call or pass of a sort run, which is shown as one row
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The id of the frame, in the order the calls happened
+    pub id: usize,
+    /// How the call is shown, like `qS(0, 9, ...)` or `gap 4`
+    pub label: String,
+    pub left: isize,
+    pub right: isize,
+    /// The indices of the events from the call until the return
+    pub events: Range<usize>,
+    /// The indices of the events of the work done by this frame itself,
+    /// after which its row shows the result
+    pub work: Range<usize>,
+    /// The ranges of elements which are in their final position once the
+    /// work is done
+    pub settled: Vec<Range<isize>>,
+    pub kind: FrameKind,
+    /// The frames called by this one, in the order they were called
+    pub children: Vec<Frame>,
+}
+
+/// What the work of a [`Frame`] consists of
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "kebab-case"
+)]
+pub enum FrameKind {
+    /// A call which returns without doing any work
+    Base,
+    /// Partitions the range around a pivot before sorting both sides
+    Partition {
+        /// The indices of the elements equal to the pivot in their final
+        /// position
+        ///
+        /// This range may be empty, in which case it only marks the split point.
+        pivot: Range<isize>,
+        /// The index the pivot was chosen from before it was moved into place
+        pivot_source: isize,
+    },
+    /// Partitions the range around two pivots before sorting the elements
+    /// below, between and above them
+    DualPartition {
+        /// The final positions of the smaller and the larger pivot
+        pivots: [isize; 2],
+    },
+    /// Partitions the range around a pivot like [`FrameKind::Partition`], but
+    /// continues only on the side which holds the element quickselect is
+    /// looking for
+    Select {
+        pivot: Range<isize>,
+        pivot_source: isize,
+        /// The sides which are not visited
+        pruned: Vec<Range<isize>>,
+        /// The index of the element looked for, if the pivot ended up there
+        result: Option<isize>,
+    },
+    /// Merges both sorted halves, which are split before `middle`
+    Merge { middle: isize },
+    /// Runs its children one after another without any work of its own
+    Sequence,
+    /// Introsort stopped partitioning and sorts the range with the passes of
+    /// another algorithm
+    Fallback { reason: Fallback },
+    /// A single pass of an iterative sort over the range
+    Pass,
+}
+
+impl Frame {
+    /// Finds the frame with the given id in this subtree
+    pub fn find(&self, id: usize) -> Option<&Frame> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// Counts the amount of frames
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(Frame::count).sum::<usize>()
+    }
+
+    /// Counts the amount of frames which are base cases
+    pub fn count_base_cases(&self) -> usize {
+        let own = usize::from(self.kind == FrameKind::Base);
+        own + self
+            .children
+            .iter()
+            .map(Frame::count_base_cases)
+            .sum::<usize>()
+    }
+
+    /// Why the recursion stopped, if this frame is a base case
+    pub fn base_case(&self) -> Option<BaseCase> {
+        match self.kind {
+            FrameKind::Base if self.right < self.left => Some(BaseCase::Empty),
+            FrameKind::Base => Some(BaseCase::Single),
+            _ => None,
+        }
+    }
+
+    /// Why introsort switched to another algorithm, if this frame is a
+    /// fallback
+    pub fn fallback(&self) -> Option<Fallback> {
+        match self.kind {
+            FrameKind::Fallback { reason } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Gets the highest recursion depth
+    pub fn max_depth(&self, depth: usize) -> usize {
+        let depth = depth + 1;
+        self.children
+            .iter()
+            .map(|child| child.max_depth(depth))
+            .fold(depth, usize::max)
+    }
+}
+
+/// The reason why introsort stopped partitioning
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fallback {
+    /// The recursion reached the depth limit, so the range is heap sorted
+    DepthLimit,
+    /// The range is at most as long as the cutoff, so it is insertion sorted
+    SmallRange,
+}
+
+impl Fallback {
+    /// A short explanation
+    pub fn reason(self) -> &'static str {
+        match self {
+            Self::DepthLimit => "depth limit reached, heap sort",
+            Self::SmallRange => "small range, insertion sort",
+        }
+    }
+}
+
+/// The reason why the recursion stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseCase {
+    /// The range contains no elements
+    Empty,
+    /// The range contains a single element
+    Single,
+}
+
+impl BaseCase {
+    /// A short explanation
+    pub fn reason(self) -> &'static str {
+        match self {
+            Self::Empty => "empty range",
+            Self::Single => "single element",
+        }
+    }
+}
@@ -1,175 +1,366 @@
 use leptos::*;
+use quicksort::{
+    algorithm::{self, Algorithm, SortOptions},
+    choice,
+    choice::Choice,
+    element::{Element, ElementKind, SortOrder},
+    input::{format_array, parse_array},
+    pivot::PivotStrategy,
+    quick_sort::quick_sort,
+    render::{Density, Layout, Renderer, SvgBackend, TableBackend, TextBackend},
+    trace::Trace,
+    trace_file::TraceFile,
+};
 
-pub struct Frame {
-    pub left: isize,
-    pub right: isize,
-    pub full: Option<FullFrame>,
-}
+use crate::{
+    bars::BarChart,
+    controls::{ChoiceSelect, GeneratorPanel, SortOptionsControls},
+    export::{ExportPanel, TraceFilePanel},
+    playback::Playback,
+    stack::StackPanel,
+    stats::{StatsDiff, StatsPanel},
+    tree::TreeLayout,
+    url::{read_fragment, write_fragment, UrlState},
+};
+
+mod bars;
+mod controls;
+mod export;
+mod playback;
+mod stack;
+mod stats;
+mod tree;
+mod url;
 
-pub struct FullFrame {
-    /// The array before the recursive calls
-    pub result: Vec<i64>,
-    /// The final pivot index
-    pub pivot: isize,
-    /// The two child frames
-    pub children: Box<[Frame; 2]>,
+/// How the recursion is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewMode {
+    /// One row per call, indented by depth
+    #[default]
+    List,
+    /// A node-link diagram of the recursion tree
+    Tree,
+    /// A bar chart of the array with animated swaps
+    Bars,
+    /// An HTML table with one row per call
+    Table,
+    /// Plain text with one line per call
+    Text,
 }
 
-impl Frame {
-    pub fn new_empty(left: isize, right: isize) -> Self {
-        Self {
-            left,
-            right,
-            full: None,
-        }
-    }
+choice!(ViewMode, "view mode", {
+    List => "list", "List",
+    Tree => "tree", "Tree",
+    Bars => "bars", "Bar chart",
+    Table => "table", "Table",
+    Text => "text", "Plain text",
+});
 
-    pub fn new(
-        left: isize,
-        right: isize,
-        result: Vec<i64>,
-        pivot: isize,
-        children: Box<[Frame; 2]>,
-    ) -> Self {
-        Self {
-            left,
-            right,
-            full: Some(FullFrame {
-                result,
-                pivot,
-                children,
-            }),
-        }
+/// Names the options of a run for the comparison view
+fn describe(options: SortOptions) -> String {
+    if !options.algorithm.uses_pivot() {
+        return options.algorithm.label().to_string();
     }
-
-    /// Counts the amount of frames
-    pub fn count(&self) -> usize {
-        1 + self
-            .full
-            .as_ref()
-            .map(|full| full.children.iter().map(Frame::count).sum())
-            .unwrap_or(0)
+    let mut description = format!(
+        "{} partition, {} pivot",
+        options.scheme.label(),
+        options.pivot.label()
+    );
+    if options.pivot == PivotStrategy::Random {
+        description += &format!(", seed {}", options.seed);
     }
-
-    /// Gets the highest recursion depth
-    pub fn max_depth(&self, depth: usize) -> usize {
-        let depth = depth + 1;
-        match &self.full {
-            Some(FullFrame { children, .. }) => children[0]
-                .max_depth(depth)
-                .max(children[1].max_depth(depth)),
-            _ => depth,
-        }
-    }
-
-    pub fn render(&self, renderer: &mut Renderer) {
-        let full = if let Some(full) = &self.full {
-            view! {
-                {full.result.iter().enumerate().map(|(i, &column)| {
-                    view! {
-                        <text x=(400 + i * 50) y=renderer.y class="text anchor-middle">{column}</text>
-                    }
-                }).collect_view()}
-                <circle cx=(400 + full.pivot * 50) cy=renderer.y class="circle" />
-            }
-            .into_view()
+    if options.algorithm == Algorithm::IterativeQuickSort {
+        let order = if options.smaller_first {
+            "smaller side first"
         } else {
-            ().into_view()
+            "left side first"
         };
-        renderer.children.push(
-            view! {
-                <text x=(10 + renderer.depth * 25) y=renderer.y class="text">"qS("{self.left}", "{self.right}", ...)"</text>
-                {full}
-            }
-            .into_view(),
-        );
-        renderer.y += 50;
-        renderer.depth += 1;
-        if let Some(full) = &self.full {
-            full.children[0].render(renderer);
-            full.children[1].render(renderer);
-        }
-        renderer.depth -= 1;
+        description = format!("Iterative, {description}, {order}");
     }
-}
-
-pub struct Renderer {
-    pub children: Vec<View>,
-    pub y: u64,
-    pub max_depth: usize,
-    pub frame_count: usize,
-    pub depth: usize,
-}
-
-impl Renderer {
-    pub fn new() -> Self {
-        Self {
-            children: Vec::new(),
-            y: 25,
-            max_depth: 0,
-            frame_count: 0,
-            depth: 0,
-        }
+    if options.algorithm == Algorithm::QuickSelect {
+        description = format!("Quickselect k = {}, {description}", options.k);
     }
-
-    pub fn render(&mut self, frame: &Frame) {
-        self.max_depth = self.max_depth.max(frame.max_depth(0));
-        self.frame_count += frame.count();
-        frame.render(self);
-    }
-
-    pub fn view_box(&self) -> String {
-        format!(
-            "0 0 {} {}",
-            800 + self.max_depth * 25,
-            self.frame_count * 50
-        )
-    }
-
-    pub fn finish(self) -> impl IntoView {
-        view! {
-            <svg viewBox=self.view_box()>
-                {self.children}
-            </svg>
-        }
+    if options.algorithm == Algorithm::IntroSort {
+        let depth_limit = match options.depth_limit {
+            0 => "2·log₂ n".to_string(),
+            limit => limit.to_string(),
+        };
+        description = format!(
+            "Introsort, {description}, depth limit {depth_limit}, cutoff {}",
+            options.cutoff
+        );
     }
+    description
 }
 
-pub fn quick_sort(array: &mut [i64], left: isize, right: isize) -> Frame {
-    if right <= left {
-        return Frame::new_empty(left, right);
-    }
-    let pivot = array[right as usize];
-    let mut i = left;
-    let mut j = right - 1;
-    while i < j {
-        while array[i as usize] < pivot && i < right {
-            i += 1;
-        }
-        while array[j as usize] > pivot && j > left {
-            j -= 1;
-        }
-        if i < j {
-            array.swap(i as usize, j as usize);
-        }
+/// Draws a trace in the chosen [`ViewMode`]
+#[component]
+fn TraceView(
+    #[prop(into)] trace: Signal<Trace<Element>>,
+    /// The amount of events which already happened
+    step: RwSignal<usize>,
+    #[prop(into)] view_mode: Signal<ViewMode>,
+    #[prop(into)] density: Signal<Density>,
+    #[prop(into)] hide_base_cases: Signal<bool>,
+) -> impl IntoView {
+    let tree_layout = create_memo(move |_| trace.with(|trace| TreeLayout::new(&trace.root)));
+    move || match view_mode() {
+        ViewMode::List => trace.with(|trace| {
+            let mut renderer =
+                Renderer::new(trace, step(), SvgBackend::new(Layout::new(density())));
+            renderer.hide_base_cases = hide_base_cases();
+            renderer.render(&trace.root);
+            renderer.finish()
+        }),
+        ViewMode::Table => trace.with(|trace| {
+            let mut renderer = Renderer::new(trace, step(), TableBackend::new());
+            renderer.hide_base_cases = hide_base_cases();
+            renderer.render(&trace.root);
+            renderer.finish()
+        }),
+        ViewMode::Text => trace.with(|trace| {
+            let mut renderer = Renderer::new(trace, step(), TextBackend::new());
+            renderer.hide_base_cases = hide_base_cases();
+            renderer.render(&trace.root);
+            view! { <pre class="trace-text">{renderer.finish()}</pre> }.into_view()
+        }),
+        ViewMode::Tree => trace.with(|trace| {
+            let step = step().min(trace.events().len());
+            let active = step.checked_sub(1).map(|last| trace.events()[last].frame);
+            tree_layout
+                .with(|layout| layout.render(step, active))
+                .into_view()
+        }),
+        ViewMode::Bars => view! { <BarChart trace=trace step=step/> }.into_view(),
     }
-    array.swap(i as usize, right as usize);
-    let result = array.to_vec();
-    let children = Box::new([
-        quick_sort(array, left, i - 1),
-        quick_sort(array, i + 1, right),
-    ]);
-    Frame::new(left, right, result, i, children)
 }
 
 #[component]
 fn App() -> impl IntoView {
-    let frame = quick_sort(&mut [3, 5, 2, 7, 8, 6, 1, 9, 3, 4], 0, 9);
-    let mut renderer = Renderer::new();
-    renderer.render(&frame);
+    let default = UrlState {
+        input: format_array(&[3, 5, 2, 7, 8, 6, 1, 9, 3, 4]),
+        kind: ElementKind::default(),
+        order: SortOrder::default(),
+        options: SortOptions::default(),
+        compare: None,
+        step: None,
+    };
+    let initial = UrlState::from_fragment(&read_fragment(), &default);
+    let options = create_rw_signal(initial.options);
+    let input = create_rw_signal(initial.input);
+    let kind = create_rw_signal(initial.kind);
+    let order = create_rw_signal(initial.order);
+    let parsed = create_memo(move |_| input.with(|input| parse_array(input, kind())));
+    // keeps showing the last valid array while the input is invalid
+    let array = create_memo(move |previous: Option<&Vec<Element>>| {
+        parsed
+            .with(|parsed| parsed.as_ref().ok().cloned())
+            .or_else(|| previous.cloned())
+            .unwrap_or_default()
+    });
+    let generate = move |array: Vec<i64>| {
+        kind.set(ElementKind::Integer);
+        input.set(format_array(&array));
+    };
+    let loaded = create_rw_signal(None::<TraceFile>);
+    let load = move |file: TraceFile| {
+        input.set(format_array(&file.trace.input));
+        kind.set(file.kind);
+        order.set(file.order);
+        options.set(file.options);
+        loaded.set(Some(file));
+    };
+    let sort = move |mut array: Vec<Element>, options: SortOptions| {
+        algorithm::sort(&mut array, options, order())
+    };
+    // keeps showing the last successful run while sorting fails
+    let last_ok = |result: Result<Trace<Element>, String>, previous: Option<&Trace<Element>>| {
+        result
+            .ok()
+            .or_else(|| previous.cloned())
+            .unwrap_or_else(|| quick_sort(&mut [], 0, -1, SortOptions::default()))
+    };
+    let result = create_memo(move |_| {
+        // a loaded trace is shown until the array or the options change
+        let matching = loaded.with(|file| {
+            file.as_ref()
+                .filter(|file| {
+                    (file.kind, file.order, file.options) == (kind(), order(), options())
+                        && array.with(|array| *array == file.trace.input)
+                })
+                .map(|file| file.trace.clone())
+        });
+        if let Some(trace) = matching {
+            return Ok(trace);
+        }
+        sort(array(), options())
+    });
+    let trace = create_memo(move |previous| last_ok(result(), previous));
+    let comparing = create_rw_signal(initial.compare.is_some());
+    let options_b = create_rw_signal(initial.compare.unwrap_or(SortOptions {
+        pivot: PivotStrategy::MedianOfThree,
+        ..initial.options
+    }));
+    // the second run of the comparison, which stays empty while not comparing
+    let result_b = create_memo(move |_| {
+        let array = if comparing() { array() } else { Vec::new() };
+        sort(array, options_b())
+    });
+    let trace_b = create_memo(move |previous| last_ok(result_b(), previous));
+    let error = |result: Memo<Result<Trace<Element>, String>>| {
+        move || {
+            result.with(|result| {
+                result.as_ref().err().map(|error| {
+                    view! { <span class="error">{error.clone()}</span> }
+                })
+            })
+        }
+    };
+    // both traces are played back in lockstep until the longer one ends
+    let len = Signal::derive(move || {
+        let len = trace.with(|trace| trace.events().len());
+        len.max(trace_b.with(|trace| trace.events().len()))
+    });
+    let (hide_base_cases, set_hide_base_cases) = create_signal(false);
+    let (show_stats, set_show_stats) = create_signal(true);
+    let view_mode = create_rw_signal(ViewMode::default());
+    let density = create_rw_signal(Density::default());
+    let view_ref = create_node_ref::<html::Div>();
+    let step = create_rw_signal(0);
+    let playing = create_rw_signal(false);
+    // restores the step from the URL once, afterwards every new trace starts at its end
+    create_effect(move |restored: Option<()>| {
+        // tracks the length in both cases, so later traces keep resetting the step
+        let len = len();
+        match (restored, initial.step) {
+            (None, Some(initial)) => step.set(initial.min(len)),
+            _ => step.set(len),
+        }
+    });
+    create_effect(move |_| {
+        let state = UrlState {
+            input: input(),
+            kind: kind(),
+            order: order(),
+            options: options(),
+            compare: comparing().then(options_b),
+            // the step is written once playback pauses instead of on every tick
+            step: if playing() {
+                None
+            } else {
+                Some(step()).filter(|&step| step < len())
+            },
+        };
+        write_fragment(&state.to_fragment());
+    });
+    window_event_listener(ev::hashchange, move |_| {
+        let state = UrlState::from_fragment(&read_fragment(), &default);
+        input.set(state.input);
+        kind.set(state.kind);
+        order.set(state.order);
+        options.set(state.options);
+        comparing.set(state.compare.is_some());
+        if let Some(compare) = state.compare {
+            options_b.set(compare);
+        }
+        step.set(state.step.unwrap_or(usize::MAX).min(len.get_untracked()));
+    });
     view! {
         <div class="app">
-            {renderer.finish()}
+            <div class="controls">
+                <label class="array-input">
+                    "Array "
+                    <input
+                        type="text"
+                        prop:value=input
+                        class:invalid=move || parsed.with(Result::is_err)
+                        on:input=move |ev| input.set(event_target_value(&ev))
+                    />
+                </label>
+                <ChoiceSelect label="Type" value=kind on_change=move |value| kind.set(value)/>
+                <ChoiceSelect label="Order" value=order on_change=move |value| order.set(value)/>
+                {move || parsed.with(|parsed| parsed.as_ref().err().map(|error| view! {
+                    <span class="error">{error.to_string()}</span>
+                }))}
+            </div>
+            <GeneratorPanel on_generate=generate/>
+            <SortOptionsControls
+                options=options
+                title=Signal::derive(move || comparing().then_some("A"))
+                len=Signal::derive(move || array.with(Vec::len))
+            >
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=comparing
+                        on:change=move |ev| comparing.set(event_target_checked(&ev))
+                    />
+                    "Compare"
+                </label>
+                {error(result)}
+            </SortOptionsControls>
+            <Show when=comparing>
+                <SortOptionsControls options=options_b title="B" len=Signal::derive(move || array.with(Vec::len))>
+                    {error(result_b)}
+                </SortOptionsControls>
+            </Show>
+            <div class="controls">
+                <ChoiceSelect label="View" value=view_mode on_change=move |mode| view_mode.set(mode)/>
+                <ChoiceSelect label="Density" value=density on_change=move |value| density.set(value)/>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=hide_base_cases
+                        on:change=move |ev| set_hide_base_cases(event_target_checked(&ev))
+                    />
+                    "Hide base cases"
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=show_stats
+                        on:change=move |ev| set_show_stats(event_target_checked(&ev))
+                    />
+                    "Statistics"
+                </label>
+            </div>
+            <ExportPanel
+                target=view_ref
+                disabled=Signal::derive(move || matches!(view_mode(), ViewMode::Table | ViewMode::Text))
+            />
+            <TraceFilePanel
+                file=Signal::derive(move || TraceFile::new(kind(), order(), options(), trace()))
+                on_load=load
+            />
+            <Playback step=step len=len playing=playing/>
+            <div class="workspace">
+                <div class="view" class:split=comparing node_ref=view_ref>
+                    <Show
+                        when=comparing
+                        fallback=move || view! { <TraceView trace=trace step=step view_mode=view_mode density=density hide_base_cases=hide_base_cases/> }
+                    >
+                        <div class="pane">
+                            <h3 class="pane-title">{move || format!("A: {}", describe(options()))}</h3>
+                            <TraceView trace=trace step=step view_mode=view_mode density=density hide_base_cases=hide_base_cases/>
+                        </div>
+                        <div class="pane">
+                            <h3 class="pane-title">{move || format!("B: {}", describe(options_b()))}</h3>
+                            <TraceView trace=trace_b step=step view_mode=view_mode density=density hide_base_cases=hide_base_cases/>
+                        </div>
+                    </Show>
+                </div>
+                <Show when=move || options().algorithm == Algorithm::IterativeQuickSort>
+                    <StackPanel trace=trace step=step title=Signal::derive(move || comparing().then_some("A"))/>
+                </Show>
+                <Show when=move || comparing() && options_b().algorithm == Algorithm::IterativeQuickSort>
+                    <StackPanel trace=trace_b step=step title="B"/>
+                </Show>
+                <Show when=show_stats>
+                    <Show when=comparing fallback=move || view! { <StatsPanel trace=trace step=step/> }>
+                        <StatsDiff a=trace b=trace_b step=step/>
+                    </Show>
+                </Show>
+            </div>
         </div>
     }
 }
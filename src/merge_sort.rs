@@ -0,0 +1,89 @@
+use std::cmp::Ordering;
+
+use crate::{
+    trace::{Trace, Tracer},
+    Frame, FrameKind,
+};
+
+struct MergeSort<F> {
+    compare: F,
+    tracer: Tracer,
+}
+
+impl<F> MergeSort<F> {
+    fn sort<T>(&mut self, array: &mut [T], left: isize, right: isize) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let entered = self.tracer.enter(left, right);
+        let label = format!("mS({left}, {right}, ...)");
+        // only the outermost call leaves its elements in their final position
+        let settled = if entered.id == 0 {
+            vec![left..right + 1]
+        } else {
+            Vec::new()
+        };
+        if right <= left {
+            let work = entered.start..entered.start + 1;
+            return self
+                .tracer
+                .exit(entered, label, work, settled, FrameKind::Base, Vec::new());
+        }
+        let middle = left + (right - left) / 2 + 1;
+        let children = vec![
+            self.sort(array, left, middle - 1),
+            self.sort(array, middle, right),
+        ];
+        let start = self.tracer.events.len();
+        // the merged order is decided first and then applied with swaps
+        let mut order = Vec::with_capacity((right - left + 1) as usize);
+        let (mut i, mut j) = (left, middle);
+        while i < middle && j <= right {
+            let ordering = self.tracer.compare_elements(array, j, i, &mut self.compare);
+            // taking from the left half on ties keeps the sort stable
+            if ordering == Ordering::Less {
+                order.push((j - left) as usize);
+                j += 1;
+            } else {
+                order.push((i - left) as usize);
+                i += 1;
+            }
+        }
+        order.extend((i..middle).chain(j..=right).map(|k| (k - left) as usize));
+        self.tracer.permute(array, left, &order);
+        let work = start..self.tracer.events.len();
+        let kind = FrameKind::Merge { middle };
+        self.tracer
+            .exit(entered, label, work, settled, kind, children)
+    }
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+pub fn merge_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut merge_sort = MergeSort {
+        compare,
+        tracer: Tracer::default(),
+    };
+    let input = array.to_vec();
+    let root = merge_sort.sort(array, left, right);
+    Trace::new(input, root, merge_sort.tracer.events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_sorts;
+
+    #[test]
+    fn merge_sort_sorts() {
+        assert_sorts("merge sort", |array, order| {
+            let right = array.len() as isize - 1;
+            merge_sort_by(array, 0, right, |a, b| order.compare(a, b))
+        });
+    }
+}
@@ -0,0 +1,111 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{rng::Rng, trace::Tracer};
+
+/// The strategy used to choose the pivot of a subarray
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PivotStrategy {
+    #[default]
+    Last,
+    First,
+    Middle,
+    /// The median of the first, middle and last element
+    MedianOfThree,
+    /// Tukey's median of three medians of three
+    Ninther,
+    /// A uniformly random element, derived from the seed
+    Random,
+}
+
+choice!(PivotStrategy, "pivot strategy", {
+    Last => "last", "Last",
+    First => "first", "First",
+    Middle => "middle", "Middle",
+    MedianOfThree => "median-of-three", "Median of three",
+    Ninther => "ninther", "Ninther",
+    Random => "random", "Random",
+});
+
+impl PivotStrategy {
+    /// Chooses the index of the pivot in `array[left..=right]`
+    pub fn select<T>(
+        self,
+        array: &[T],
+        left: isize,
+        right: isize,
+        rng: &mut Rng,
+        tracer: &mut Tracer,
+        compare: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> isize {
+        let middle = left + (right - left) / 2;
+        let mut median = |a, b, c| median_of_three(array, tracer, compare, a, b, c);
+        match self {
+            Self::Last => right,
+            Self::First => left,
+            Self::Middle => middle,
+            Self::MedianOfThree => median(left, middle, right),
+            Self::Ninther => {
+                let step = (right - left + 1) / 8;
+                if step == 0 {
+                    return median(left, middle, right);
+                }
+                let a = median(left, left + step, left + 2 * step);
+                let b = median(middle - step, middle, middle + step);
+                let c = median(right - 2 * step, right - step, right);
+                median(a, b, c)
+            }
+            Self::Random => left + rng.below((right - left + 1) as u64) as isize,
+        }
+    }
+}
+
+/// Returns the index of the median of the three indexed elements
+///
+/// Orders the first two elements and places the third with at most two more
+/// comparisons, so duplicates never make it pick an extreme.
+fn median_of_three<T>(
+    array: &[T],
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+    a: isize,
+    b: isize,
+    c: isize,
+) -> isize {
+    let mut greater = |x, y| tracer.compare_elements(array, x, y, compare) == Ordering::Greater;
+    let (low, mut middle) = if greater(a, b) { (b, a) } else { (a, b) };
+    // the larger of `low` and `c` is the median if `c` is below `middle`
+    if greater(middle, c) {
+        middle = if greater(low, c) { low } else { c };
+    }
+    middle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn median(array: &[i64]) -> i64 {
+        let mut tracer = Tracer::default();
+        array[median_of_three(array, &mut tracer, &mut i64::cmp, 0, 1, 2) as usize]
+    }
+
+    #[test]
+    fn median_of_three_handles_duplicates() {
+        for array in [
+            [5, 5, 3],
+            [3, 5, 5],
+            [5, 3, 5],
+            [1, 2, 3],
+            [3, 2, 1],
+            [2, 3, 1],
+            [4, 4, 4],
+        ] {
+            let mut sorted = array;
+            sorted.sort();
+            assert_eq!(median(&array), sorted[1], "{array:?}");
+        }
+    }
+}
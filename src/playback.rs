@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use leptos::{leptos_dom::helpers::IntervalHandle, *};
+
+/// Controls which step of a trace is shown
+#[component]
+pub fn Playback(
+    /// The amount of events which already happened
+    step: RwSignal<usize>,
+    /// The amount of events in the trace
+    #[prop(into)]
+    len: Signal<usize>,
+    /// Whether the steps advance on their own
+    playing: RwSignal<bool>,
+) -> impl IntoView {
+    // steps per second
+    let (speed, set_speed) = create_signal(4u64);
+    let interval = store_value(None::<IntervalHandle>);
+    create_effect(move |_| {
+        if let Some(handle) = interval.get_value() {
+            handle.clear();
+        }
+        interval.set_value(None);
+        if !playing() {
+            return;
+        }
+        let handle = set_interval_with_handle(
+            move || {
+                let len = len.get_untracked();
+                if step.get_untracked() >= len {
+                    playing.set(false);
+                } else {
+                    step.update(|step| *step += 1);
+                }
+            },
+            Duration::from_millis(1000 / speed()),
+        );
+        interval.set_value(handle.ok());
+    });
+    on_cleanup(move || {
+        if let Some(handle) = interval.get_value() {
+            handle.clear();
+        }
+    });
+    let toggle = move |_| {
+        if !playing() && step() >= len() {
+            step.set(0);
+        }
+        playing.update(|playing| *playing = !*playing);
+    };
+    view! {
+        <div class="controls playback">
+            <button title="Jump to start" on:click=move |_| step.set(0)>"⏮"</button>
+            <button title="Step back" on:click=move |_| step.update(|step| *step = step.saturating_sub(1))>"⏪"</button>
+            <button title="Play/Pause" on:click=toggle>{move || if playing() { "⏸" } else { "▶" }}</button>
+            <button title="Step forward" on:click=move |_| step.update(|step| *step = (*step + 1).min(len()))>"⏩"</button>
+            <button title="Jump to end" on:click=move |_| step.set(len())>"⏭"</button>
+            <input
+                class="timeline"
+                type="range"
+                min="0"
+                max=move || len().to_string()
+                prop:value=move || step().to_string()
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<usize>() {
+                        step.set(value.min(len()));
+                    }
+                }
+            />
+            <span>{move || format!("{} / {}", step(), len())}</span>
+            <label>
+                "Speed "
+                <input
+                    type="range"
+                    min="1"
+                    max="60"
+                    prop:value=move || speed().to_string()
+                    on:input=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            set_speed(value);
+                        }
+                    }
+                />
+            </label>
+        </div>
+    }
+}
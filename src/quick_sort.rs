@@ -0,0 +1,671 @@
+use std::{cmp::Ordering, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    algorithm::SortOptions,
+    heap_sort::heap_passes,
+    insertion_sort::insertion_passes,
+    rng::Rng,
+    trace::{Step, Trace, Tracer},
+    Fallback, Frame, FrameKind,
+};
+
+/// The strategy used to partition a subarray around its pivot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PartitionScheme {
+    /// Single forward scan with the pivot in the last slot
+    #[default]
+    Lomuto,
+    /// Two indices scanning towards each other with the pivot in the first slot
+    Hoare,
+    /// Dijkstra's Dutch national flag partitioning into `<`, `=` and `>`
+    ThreeWay,
+}
+
+choice!(PartitionScheme, "partition scheme", {
+    Lomuto => "lomuto", "Lomuto",
+    Hoare => "hoare", "Hoare",
+    ThreeWay => "three-way", "Three-way",
+});
+
+impl PartitionScheme {
+    /// The slot the pivot has to be moved into before partitioning
+    pub fn pivot_slot(self, left: isize, right: isize) -> isize {
+        match self {
+            Self::Lomuto => right,
+            Self::Hoare | Self::ThreeWay => left,
+        }
+    }
+
+    /// Partitions `array[left..=right]` and returns the range of elements
+    /// which are in their final position.
+    ///
+    /// The range is empty for [`PartitionScheme::Hoare`], where it only marks
+    /// the split between both halves.
+    pub fn partition<T: Clone>(
+        self,
+        array: &mut [T],
+        left: isize,
+        right: isize,
+        tracer: &mut Tracer,
+        compare: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> Range<isize> {
+        match self {
+            Self::Lomuto => lomuto(array, left, right, tracer, compare),
+            Self::Hoare => hoare(array, left, right, tracer, compare),
+            Self::ThreeWay => three_way(array, left, right, tracer, compare),
+        }
+    }
+}
+
+fn lomuto<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Range<isize> {
+    let pivot = array[right as usize].clone();
+    let mut i = left;
+    for j in left..right {
+        if tracer.compare(array, j, &pivot, compare) == Ordering::Less {
+            tracer.swap(array, i, j);
+            i += 1;
+            tracer.push(Step::Advance { index: i });
+        }
+    }
+    tracer.swap(array, i, right);
+    i..i + 1
+}
+
+fn hoare<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Range<isize> {
+    let pivot = array[left as usize].clone();
+    let mut i = left - 1;
+    let mut j = right + 1;
+    loop {
+        i += 1;
+        tracer.push(Step::Advance { index: i });
+        while tracer.compare(array, i, &pivot, compare) == Ordering::Less {
+            i += 1;
+            tracer.push(Step::Advance { index: i });
+        }
+        j -= 1;
+        tracer.push(Step::Retreat { index: j });
+        while tracer.compare(array, j, &pivot, compare) == Ordering::Greater {
+            j -= 1;
+            tracer.push(Step::Retreat { index: j });
+        }
+        if i >= j {
+            return j + 1..j + 1;
+        }
+        tracer.swap(array, i, j);
+    }
+}
+
+fn three_way<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    tracer: &mut Tracer,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Range<isize> {
+    let pivot = array[left as usize].clone();
+    let mut lt = left;
+    let mut i = left;
+    let mut gt = right;
+    while i <= gt {
+        match tracer.compare(array, i, &pivot, compare) {
+            Ordering::Less => {
+                tracer.swap(array, lt, i);
+                lt += 1;
+                i += 1;
+                tracer.push(Step::Advance { index: i });
+            }
+            Ordering::Greater => {
+                tracer.swap(array, i, gt);
+                gt -= 1;
+                tracer.push(Step::Retreat { index: gt });
+            }
+            Ordering::Equal => {
+                i += 1;
+                tracer.push(Step::Advance { index: i });
+            }
+        }
+    }
+    lt..gt + 1
+}
+
+/// The limits after which introsort stops partitioning
+#[derive(Clone, Copy, Debug)]
+struct Intro {
+    depth_limit: usize,
+    cutoff: usize,
+}
+
+struct QuickSort<F> {
+    options: SortOptions,
+    compare: F,
+    rng: Rng,
+    tracer: Tracer,
+    /// Set for introsort
+    intro: Option<Intro>,
+    /// The depth of the current call
+    depth: usize,
+}
+
+impl<F> QuickSort<F> {
+    fn sort<T: Clone>(&mut self, array: &mut [T], left: isize, right: isize) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let fallback = self.intro.and_then(|intro| {
+            if right <= left {
+                None
+            } else if (right - left + 1) as usize <= intro.cutoff {
+                Some(Fallback::SmallRange)
+            } else if self.depth >= intro.depth_limit {
+                Some(Fallback::DepthLimit)
+            } else {
+                None
+            }
+        });
+        if let Some(reason) = fallback {
+            return self.fall_back(array, left, right, reason);
+        }
+        let entered = self.tracer.enter(left, right);
+        let label = format!("qS({left}, {right}, ...)");
+        if right <= left {
+            let work = entered.start..entered.start + 1;
+            return self.tracer.exit(
+                entered,
+                label,
+                work,
+                vec![left..right + 1],
+                FrameKind::Base,
+                Vec::new(),
+            );
+        }
+        let (source, pivot) = self.partition(array, left, right);
+        let work = entered.start..self.tracer.events.len();
+        self.depth += 1;
+        let children = vec![
+            self.sort(array, left, pivot.start - 1),
+            self.sort(array, pivot.end, right),
+        ];
+        self.depth -= 1;
+        let kind = FrameKind::Partition {
+            pivot: pivot.clone(),
+            pivot_source: source,
+        };
+        self.tracer
+            .exit(entered, label, work, vec![pivot], kind, children)
+    }
+
+    /// Chooses a pivot and partitions `array[left..=right]` around it
+    ///
+    /// Returns the index the pivot was chosen from and its final position.
+    fn partition<T: Clone>(
+        &mut self,
+        array: &mut [T],
+        left: isize,
+        right: isize,
+    ) -> (isize, Range<isize>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let scheme = self.options.scheme;
+        let source = self.options.pivot.select(
+            array,
+            left,
+            right,
+            &mut self.rng,
+            &mut self.tracer,
+            &mut self.compare,
+        );
+        self.tracer.push(Step::ChoosePivot { index: source });
+        let slot = scheme.pivot_slot(left, right);
+        if source != slot {
+            self.tracer.swap(array, source, slot);
+        }
+        let pivot = scheme.partition(array, left, right, &mut self.tracer, &mut self.compare);
+        self.tracer.push(Step::PlacePivot {
+            range: pivot.clone(),
+        });
+        (source, pivot)
+    }
+
+    /// Moves the element which belongs to index `k` in sorted order there,
+    /// partitioning only the side of `array[left..=right]` which holds it
+    fn select<T: Clone>(&mut self, array: &mut [T], left: isize, right: isize, k: isize) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let entered = self.tracer.enter(left, right);
+        let label = format!("qSel({left}, {right}, ...)");
+        if right < left {
+            let work = entered.start..entered.start + 1;
+            return self.tracer.exit(
+                entered,
+                label,
+                work,
+                Vec::new(),
+                FrameKind::Base,
+                Vec::new(),
+            );
+        }
+        // a single element is its own pivot
+        let (source, pivot) = if left == right {
+            self.tracer.push(Step::ChoosePivot { index: left });
+            self.tracer.push(Step::PlacePivot {
+                range: left..left + 1,
+            });
+            (left, left..left + 1)
+        } else {
+            self.partition(array, left, right)
+        };
+        let work = entered.start..self.tracer.events.len();
+        let lower = left..pivot.start;
+        let upper = pivot.end..right + 1;
+        let (pruned, children, result) = if k < pivot.start {
+            let child = self.select(array, left, pivot.start - 1, k);
+            (vec![upper], vec![child], None)
+        } else if k >= pivot.end {
+            let child = self.select(array, pivot.end, right, k);
+            (vec![lower], vec![child], None)
+        } else {
+            (vec![lower, upper], Vec::new(), Some(k))
+        };
+        let kind = FrameKind::Select {
+            pivot: pivot.clone(),
+            pivot_source: source,
+            pruned: pruned.into_iter().filter(|side| !side.is_empty()).collect(),
+            result,
+        };
+        self.tracer
+            .exit(entered, label, work, vec![pivot], kind, children)
+    }
+
+    /// Sorts `array[left..=right]` in a loop over an explicit stack of
+    /// pending ranges instead of recursing
+    ///
+    /// Every popped range becomes a child of a single root frame, so the
+    /// calls never nest.
+    fn sort_iteratively<T: Clone>(&mut self, array: &mut [T], left: isize, right: isize) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let entered = self.tracer.enter(left, right);
+        let label = format!("iqS({left}, {right}, ...)");
+        let work = entered.start..entered.start + 1;
+        if right <= left {
+            let settled = vec![left..right + 1];
+            return self
+                .tracer
+                .exit(entered, label, work, settled, FrameKind::Base, Vec::new());
+        }
+        let mut stack = vec![(left, right)];
+        self.tracer.push(Step::PushRange { left, right });
+        let mut children = Vec::new();
+        while let Some((left, right)) = stack.pop() {
+            self.tracer.push(Step::PopRange);
+            let entered = self.tracer.enter(left, right);
+            let label = format!("qS({left}, {right}, ...)");
+            if right <= left {
+                let work = entered.start..entered.start + 1;
+                children.push(self.tracer.exit(
+                    entered,
+                    label,
+                    work,
+                    vec![left..right + 1],
+                    FrameKind::Base,
+                    Vec::new(),
+                ));
+                continue;
+            }
+            let (source, pivot) = self.partition(array, left, right);
+            let work = entered.start..self.tracer.events.len();
+            let kind = FrameKind::Partition {
+                pivot: pivot.clone(),
+                pivot_source: source,
+            };
+            children.push(self.tracer.exit(
+                entered,
+                label,
+                work,
+                vec![pivot.clone()],
+                kind,
+                Vec::new(),
+            ));
+            let lower = (left, pivot.start - 1);
+            let upper = (pivot.end, right);
+            // the range pushed last is sorted next
+            let smaller_lower = pivot.start - left <= right - pivot.end + 1;
+            let next = if self.options.smaller_first && !smaller_lower {
+                [lower, upper]
+            } else {
+                [upper, lower]
+            };
+            for (left, right) in next {
+                stack.push((left, right));
+                self.tracer.push(Step::PushRange { left, right });
+            }
+        }
+        self.tracer.exit(
+            entered,
+            label,
+            work,
+            Vec::new(),
+            FrameKind::Sequence,
+            children,
+        )
+    }
+
+    /// Sorts `array[left..=right]` with the algorithm introsort falls back to
+    fn fall_back<T>(
+        &mut self,
+        array: &mut [T],
+        left: isize,
+        right: isize,
+        reason: Fallback,
+    ) -> Frame
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let entered = self.tracer.enter(left, right);
+        let work = entered.start..entered.start + 1;
+        let (label, children) = match reason {
+            Fallback::DepthLimit => (
+                format!("hS({left}, {right}, ...)"),
+                heap_passes(array, left, right, &mut self.tracer, &mut self.compare),
+            ),
+            Fallback::SmallRange => (
+                format!("iS({left}, {right}, ...)"),
+                insertion_passes(array, left, right, &mut self.tracer, &mut self.compare),
+            ),
+        };
+        let kind = FrameKind::Fallback { reason };
+        self.tracer
+            .exit(entered, label, work, Vec::new(), kind, children)
+    }
+}
+
+/// Sorts `array[left..=right]` in ascending order and traces every step
+pub fn quick_sort<T: Ord + Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    options: SortOptions,
+) -> Trace<T> {
+    quick_sort_by(array, left, right, options, T::cmp)
+}
+
+/// Sorts `array[left..=right]` by a custom comparator and traces every step
+pub fn quick_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    options: SortOptions,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut quick_sort = QuickSort {
+        options,
+        compare,
+        rng: Rng::new(options.seed),
+        tracer: Tracer::default(),
+        intro: None,
+        depth: 0,
+    };
+    let input = array.to_vec();
+    let root = quick_sort.sort(array, left, right);
+    Trace::new(input, root, quick_sort.tracer.events)
+}
+
+/// Moves the element which belongs to index `k` in sorted order there, with
+/// smaller elements before it and larger ones after it
+///
+/// `k` has to be within `left..=right` unless the range is empty.
+pub fn quick_select_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    k: isize,
+    options: SortOptions,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut quick_sort = QuickSort {
+        options,
+        compare,
+        rng: Rng::new(options.seed),
+        tracer: Tracer::default(),
+        intro: None,
+        depth: 0,
+    };
+    let input = array.to_vec();
+    let root = quick_sort.select(array, left, right, k);
+    Trace::new(input, root, quick_sort.tracer.events)
+}
+
+/// Sorts `array[left..=right]` like [`quick_sort_by`], but without recursion
+pub fn iterative_quick_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    options: SortOptions,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let mut quick_sort = QuickSort {
+        options,
+        compare,
+        rng: Rng::new(options.seed),
+        tracer: Tracer::default(),
+        intro: None,
+        depth: 0,
+    };
+    let input = array.to_vec();
+    let root = quick_sort.sort_iteratively(array, left, right);
+    Trace::new(input, root, quick_sort.tracer.events)
+}
+
+/// Sorts `array[left..=right]` like [`quick_sort_by`], but switches to heap
+/// sort below the depth limit and to insertion sort for ranges up to the
+/// cutoff of `options`
+pub fn intro_sort_by<T: Clone>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    options: SortOptions,
+    compare: impl FnMut(&T, &T) -> Ordering,
+) -> Trace<T> {
+    let len = (right - left + 1).max(1) as usize;
+    let depth_limit = match options.depth_limit {
+        0 => 2 * len.ilog2() as usize,
+        limit => limit,
+    };
+    let mut quick_sort = QuickSort {
+        options,
+        compare,
+        rng: Rng::new(options.seed),
+        tracer: Tracer::default(),
+        intro: Some(Intro {
+            depth_limit,
+            cutoff: options.cutoff,
+        }),
+        depth: 0,
+    };
+    let input = array.to_vec();
+    let root = quick_sort.sort(array, left, right);
+    Trace::new(input, root, quick_sort.tracer.events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithm::{self, Algorithm},
+        choice::Choice,
+        element::SortOrder,
+        pivot::PivotStrategy,
+        testing::{assert_sorts, ARRAYS},
+    };
+
+    #[test]
+    fn every_scheme_and_pivot_sorts() {
+        for &scheme in PartitionScheme::ALL {
+            for &pivot in PivotStrategy::ALL {
+                let options = SortOptions {
+                    scheme,
+                    pivot,
+                    seed: 7,
+                    ..SortOptions::default()
+                };
+                assert_sorts(&format!("{scheme} {pivot}"), |array, order| {
+                    let right = array.len() as isize - 1;
+                    quick_sort_by(array, 0, right, options, |a, b| order.compare(a, b))
+                });
+            }
+        }
+    }
+
+    /// The fallbacks in the tree of `frame` with their ranges, in call order
+    fn fallbacks(frame: &Frame) -> Vec<(Fallback, isize, isize)> {
+        let own = frame
+            .fallback()
+            .map(|reason| (reason, frame.left, frame.right));
+        own.into_iter()
+            .chain(frame.children.iter().flat_map(fallbacks))
+            .collect()
+    }
+
+    #[test]
+    fn intro_sort_falls_back() {
+        let intro_sort = |depth_limit, cutoff| {
+            let options = SortOptions {
+                depth_limit,
+                cutoff,
+                ..SortOptions::default()
+            };
+            let mut array: Vec<i64> = (0..16).collect();
+            intro_sort_by(&mut array, 0, 15, options, i64::cmp)
+        };
+        // the last element of a sorted range leaves all others to one side
+        let trace = intro_sort(3, 1);
+        assert_eq!(fallbacks(&trace.root), [(Fallback::DepthLimit, 0, 12)]);
+        let trace = intro_sort(100, 4);
+        assert_eq!(fallbacks(&trace.root), [(Fallback::SmallRange, 0, 3)]);
+        for (depth_limit, cutoff) in [(0, 4), (1, 1), (3, 8), (100, 16)] {
+            let options = SortOptions {
+                depth_limit,
+                cutoff,
+                ..SortOptions::default()
+            };
+            assert_sorts("introsort", |array, order| {
+                let right = array.len() as isize - 1;
+                intro_sort_by(array, 0, right, options, |a, b| order.compare(a, b))
+            });
+        }
+    }
+
+    #[test]
+    fn iterative_quick_sort_bounds_the_stack() {
+        let iterative = |array: &mut [i64], smaller_first| {
+            let options = SortOptions {
+                smaller_first,
+                ..SortOptions::default()
+            };
+            let right = array.len() as isize - 1;
+            let trace = iterative_quick_sort_by(array, 0, right, options, i64::cmp);
+            trace.stats_at(trace.events().len()).max_stack
+        };
+        let mut rng = Rng::new(3);
+        for len in [1, 2, 5, 16, 63, 64, 100] {
+            let bound = (len as u32).ilog2() as usize + 1;
+            let sorted: Vec<i64> = (0..len).collect();
+            let shuffled: Vec<i64> = (0..len).map(|_| rng.below(50) as i64).collect();
+            for input in [&sorted, &shuffled] {
+                let max_stack = iterative(&mut input.clone(), true);
+                assert!(max_stack <= bound, "{len}: {max_stack} > {bound}");
+            }
+            // without it, every partition of a sorted range leaves one more
+            // range on the stack
+            if len > 2 {
+                let max_stack = iterative(&mut sorted.clone(), false);
+                assert!(max_stack > bound, "{len}: {max_stack} <= {bound}");
+            }
+        }
+        assert_sorts("iterative quicksort", |array, order| {
+            let right = array.len() as isize - 1;
+            let options = SortOptions::default();
+            iterative_quick_sort_by(array, 0, right, options, |a, b| order.compare(a, b))
+        });
+    }
+
+    /// Checks that the pivot, the pruned sides and the child of every
+    /// selection in the tree of `frame` cover its range without overlap, with
+    /// `k` outside of the pruned sides, and returns the last selection
+    fn last_selection(frame: &Frame, k: isize) -> &Frame {
+        let FrameKind::Select {
+            ref pivot,
+            ref pruned,
+            ..
+        } = frame.kind
+        else {
+            panic!("{frame:?} is not a selection");
+        };
+        let mut parts = pruned.clone();
+        parts.push(pivot.clone());
+        parts.extend(
+            frame
+                .children
+                .iter()
+                .map(|child| child.left..child.right + 1),
+        );
+        parts.sort_by_key(|part| part.start);
+        let covered = parts.iter().try_fold(frame.left, |start, part| {
+            (part.start == start).then_some(part.end)
+        });
+        assert_eq!(covered, Some(frame.right + 1), "{frame:?}");
+        assert!(pruned.iter().all(|side| !side.contains(&k)), "{frame:?}");
+        match frame.children.first() {
+            Some(child) => last_selection(child, k),
+            None => frame,
+        }
+    }
+
+    #[test]
+    fn quick_select_finds_the_kth_element() {
+        for input in ARRAYS.into_iter().filter(|input| !input.is_empty()) {
+            let mut sorted = input.to_vec();
+            sorted.sort();
+            for k in 0..input.len() {
+                let mut array = input.to_vec();
+                let right = input.len() as isize - 1;
+                let options = SortOptions::default();
+                let trace = quick_select_by(&mut array, 0, right, k as isize, options, i64::cmp);
+                assert_eq!(array[k], sorted[k], "{input:?} {k}");
+                assert!(array[..k].iter().all(|&element| element <= sorted[k]));
+                assert!(array[k + 1..].iter().all(|&element| element >= sorted[k]));
+                assert_eq!(trace.array_at(trace.events().len()), array);
+                let last = last_selection(&trace.root, k as isize);
+                assert!(
+                    matches!(last.kind, FrameKind::Select { result, .. } if result == Some(k as isize)),
+                    "{input:?} {k}: {last:?}"
+                );
+            }
+        }
+        let options = SortOptions {
+            algorithm: Algorithm::QuickSelect,
+            k: 3,
+            ..SortOptions::default()
+        };
+        let mut array = vec![3, 1, 2];
+        assert!(algorithm::sort(&mut array, options, SortOrder::Ascending).is_err());
+    }
+}
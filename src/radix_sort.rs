@@ -0,0 +1,98 @@
+use crate::{
+    element::{Element, SortOrder},
+    trace::{Trace, Tracer},
+};
+
+/// The base of the digits
+const RADIX: u128 = 10;
+
+/// Elements which can be sorted digit by digit
+pub trait RadixKey {
+    /// An integer with the same order as the element, if there is one
+    fn radix_key(&self) -> Option<i128>;
+}
+
+impl RadixKey for Element {
+    fn radix_key(&self) -> Option<i128> {
+        match self {
+            Self::Integer(value) => Some(*value as i128),
+            Self::Char(value) => Some(*value as i128),
+            Self::Float(_) | Self::String(_) | Self::Pair(..) => None,
+        }
+    }
+}
+
+impl RadixKey for i64 {
+    fn radix_key(&self) -> Option<i128> {
+        Some(*self as i128)
+    }
+}
+
+/// Sorts `array[left..=right]` in `order` and traces every step
+///
+/// Every digit is a separate pass which moves the elements into a stable
+/// order of that digit. Fails if an element has no [`RadixKey`].
+pub fn radix_sort<T: Clone + RadixKey>(
+    array: &mut [T],
+    left: isize,
+    right: isize,
+    sort_order: SortOrder,
+) -> Result<Trace<T>, String> {
+    let range = left.max(0) as usize..(right + 1).max(left) as usize;
+    let keys = array[range]
+        .iter()
+        .map(T::radix_key)
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| "radix sort needs integers or characters".to_string())?;
+    // negative keys are shifted, so that the digits of the others stay intact
+    let min = keys.iter().copied().min().unwrap_or(0).min(0);
+    let mut keys: Vec<u128> = keys.into_iter().map(|key| (key - min) as u128).collect();
+    let max = keys.iter().copied().max().unwrap_or(0);
+    let mut places = vec![1];
+    while let Some(&place) = places.last().filter(|&&place| max / place >= RADIX) {
+        places.push(place * RADIX);
+    }
+    let mut tracer = Tracer::default();
+    let input = array.to_vec();
+    let label = format!("rS({left}, {right}, ...)");
+    let root = tracer.sequence(left, right, label, |tracer| {
+        let last = places.len() - 1;
+        places
+            .iter()
+            .enumerate()
+            .map(|(i, &place)| {
+                let settled = if i == last {
+                    vec![left..right + 1]
+                } else {
+                    Vec::new()
+                };
+                tracer.pass(left, right, format!("digit {place}"), settled, |tracer| {
+                    let digit = |key: u128| match sort_order {
+                        SortOrder::Ascending => key / place % RADIX,
+                        SortOrder::Descending => RADIX - 1 - key / place % RADIX,
+                    };
+                    let mut order: Vec<usize> = (0..keys.len()).collect();
+                    // a stable sort by the digit, just like counting into buckets
+                    order.sort_by_key(|&i| digit(keys[i]));
+                    tracer.permute(array, left, &order);
+                    keys = order.iter().map(|&i| keys[i]).collect();
+                })
+            })
+            .collect()
+    });
+    Ok(Trace::new(input, root, tracer.events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_sorts;
+
+    #[test]
+    fn radix_sort_sorts() {
+        assert_sorts("radix sort", |array, order| {
+            let right = array.len() as isize - 1;
+            radix_sort(array, 0, right, order).unwrap()
+        });
+    }
+}
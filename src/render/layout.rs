@@ -0,0 +1,127 @@
+/// The pixel metrics of the [`SvgBackend`](super::SvgBackend)
+///
+/// Every coordinate of the list view and its `viewBox` is derived from
+/// these, so changing one keeps the drawing consistent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// The height of a row, which is also the height of the window
+    pub row_height: u32,
+    /// The narrowest an array column gets
+    pub min_column_width: u32,
+    /// The approximate width of a character of the monospace font
+    pub char_width: u32,
+    /// The space around the widest label of a column
+    pub column_padding: u32,
+    /// The font size of labels and cells
+    pub font_size: u32,
+    /// The space left of the call labels
+    pub margin: u32,
+    /// The indentation per recursion level
+    pub indent: u32,
+    /// The space reserved for the call label right of its indentation
+    pub label_width: u32,
+    /// The space between the array and its notes
+    pub note_padding: u32,
+    /// The narrowest the whole drawing gets
+    pub min_width: u32,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(Density::default())
+    }
+}
+
+impl Layout {
+    pub fn new(density: Density) -> Self {
+        match density {
+            Density::Compact => Self {
+                row_height: 30,
+                min_column_width: 30,
+                char_width: 8,
+                column_padding: 12,
+                font_size: 13,
+                margin: 5,
+                indent: 12,
+                label_width: 230,
+                note_padding: 6,
+                min_width: 500,
+            },
+            Density::Normal => Self {
+                row_height: 50,
+                min_column_width: 50,
+                char_width: 12,
+                column_padding: 26,
+                font_size: 20,
+                margin: 10,
+                indent: 25,
+                label_width: 365,
+                note_padding: 10,
+                min_width: 800,
+            },
+            Density::Spacious => Self {
+                row_height: 70,
+                min_column_width: 70,
+                char_width: 15,
+                column_padding: 36,
+                font_size: 26,
+                margin: 15,
+                indent: 35,
+                label_width: 450,
+                note_padding: 14,
+                min_width: 1000,
+            },
+        }
+    }
+
+    /// The width of a column fitting labels of `widest` characters
+    pub fn column_width(&self, widest: usize) -> u32 {
+        (widest as u32 * self.char_width + self.column_padding).max(self.min_column_width)
+    }
+
+    /// The space right of the array fitting notes of `longest` characters
+    pub fn note_width(&self, longest: usize) -> u32 {
+        if longest == 0 {
+            0
+        } else {
+            self.note_padding + longest as u32 * self.char_width
+        }
+    }
+
+    /// The horizontal radius of the marker around the pivot
+    pub fn circle_rx(&self, column_width: u32) -> u32 {
+        column_width / 2 - 3
+    }
+
+    /// The vertical radius of the marker around the pivot
+    pub fn circle_ry(&self) -> u32 {
+        self.row_height * 11 / 25
+    }
+
+    /// The horizontal radius of the marker where the pivot came from
+    pub fn source_rx(&self, column_width: u32) -> u32 {
+        column_width / 2 - 7
+    }
+
+    /// The vertical radius of the marker where the pivot came from
+    pub fn source_ry(&self) -> u32 {
+        self.row_height * 9 / 25
+    }
+}
+
+/// How much space the list view uses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Density {
+    /// Small rows for long arrays and deep recursions
+    Compact,
+    #[default]
+    Normal,
+    /// Large rows for projectors
+    Spacious,
+}
+
+choice!(Density, "density", {
+    Compact => "compact", "Compact",
+    Normal => "normal", "Normal",
+    Spacious => "spacious", "Spacious",
+});
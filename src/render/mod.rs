@@ -0,0 +1,384 @@
+use std::{fmt::Display, ops::Range};
+
+use crate::{
+    trace::{Event, Step, Trace},
+    BaseCase, Fallback, Frame, FrameKind,
+};
+
+pub use self::{
+    layout::{Density, Layout},
+    svg::{Geometry, Shape, StaticSvgBackend, SvgBackend},
+    table::TableBackend,
+    text::TextBackend,
+};
+
+mod layout;
+mod svg;
+mod table;
+mod text;
+
+/// How a single array cell of a row is shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellState {
+    /// Outside of the range of the call
+    Outside,
+    /// Inside of the range of the call
+    Inside,
+    /// Compared or swapped by the last event
+    Touched,
+    /// Equal to the pivot and placed by this call
+    Pivot,
+    /// The larger of two pivots placed by this call
+    UpperPivot,
+    /// The element quickselect was looking for
+    Result,
+    /// On a side which quickselect does not visit
+    Pruned,
+    /// Placed into its final position by an earlier call or pass
+    Final,
+}
+
+impl CellState {
+    /// The CSS class of the cell
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::Outside => "dimmed",
+            Self::Inside => "",
+            Self::Touched => "touched",
+            Self::Pivot => "pivot",
+            Self::UpperPivot => "upper-pivot",
+            Self::Result => "result",
+            Self::Pruned => "pruned",
+            Self::Final => "final",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub label: String,
+    pub state: CellState,
+}
+
+/// A single call or pass, ready to be drawn by a [`Backend`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub id: usize,
+    pub label: String,
+    /// The recursion depth, starting at 0
+    pub depth: usize,
+    pub left: isize,
+    pub right: isize,
+    /// Whether the call is currently running
+    pub active: bool,
+    pub cells: Vec<Cell>,
+    /// The final position of the pivot, once it is placed
+    pub pivot: Option<Range<isize>>,
+    /// The final position of the larger pivot of a dual-pivot partition, once
+    /// it is placed
+    pub upper_pivot: Option<isize>,
+    /// The index before which the range is split in two halves
+    pub split: Option<isize>,
+    /// The index the pivot was chosen from
+    pub pivot_source: Option<isize>,
+    /// The sides a selection does not visit, once it partitioned the range
+    pub pruned: Vec<Range<isize>>,
+    /// The index of the element a selection was looking for, once it is found
+    pub result: Option<isize>,
+    /// Why the recursion stopped, for base cases
+    pub base_case: Option<BaseCase>,
+    /// Why introsort switched to another algorithm, for fallback calls
+    pub fallback: Option<Fallback>,
+}
+
+impl Row {
+    /// The note shown right of the array, if any
+    pub fn note(&self) -> Option<String> {
+        note(self.base_case, self.fallback, self.result)
+    }
+}
+
+/// The dimensions of everything a [`Renderer`] is going to draw
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    /// The length of the array
+    pub len: usize,
+    /// The amount of characters of the widest array label
+    pub widest: usize,
+    /// The highest recursion depth
+    pub max_depth: usize,
+    /// The amount of characters of the longest call label
+    pub longest_label: usize,
+    /// The amount of characters of the longest note, 0 without notes
+    pub longest_note: usize,
+    /// The amount of rows, including the ones which are not visible yet
+    pub rows: usize,
+}
+
+/// An output format of a [`Renderer`]
+pub trait Backend {
+    type Output;
+
+    /// Called once before the first row
+    fn begin(&mut self, summary: &Summary);
+
+    /// Called for every visible call in the order the calls happened
+    fn row(&mut self, row: Row);
+
+    fn finish(self) -> Self::Output;
+}
+
+/// Walks a [`Frame`] tree and hands the visible calls to a [`Backend`]
+pub struct Renderer<'a, T, B> {
+    pub backend: B,
+    pub trace: &'a Trace<T>,
+    /// The amount of events which already happened
+    pub step: usize,
+    /// The labels of the input elements
+    pub labels: Vec<String>,
+    /// The event which happened last
+    pub last: Option<Event>,
+    /// The amount of events after which each element is in its final position
+    pub settled: Vec<Option<usize>>,
+    /// Whether base case frames are left out
+    pub hide_base_cases: bool,
+    pub depth: usize,
+}
+
+impl<'a, T, B: Backend> Renderer<'a, T, B> {
+    pub fn new(trace: &'a Trace<T>, step: usize, backend: B) -> Self
+    where
+        T: Display,
+    {
+        let step = step.min(trace.events().len());
+        Self {
+            backend,
+            trace,
+            step,
+            labels: trace.input.iter().map(T::to_string).collect(),
+            last: step.checked_sub(1).map(|last| trace.events()[last].clone()),
+            settled: vec![None; trace.input.len()],
+            hide_base_cases: false,
+            depth: 0,
+        }
+    }
+
+    /// The id of the frame which is currently running
+    pub fn active(&self) -> Option<usize> {
+        self.last.as_ref().map(|event| event.frame)
+    }
+
+    /// The indices the last event touched
+    pub fn touched(&self) -> Vec<isize> {
+        match self.last.as_ref().map(|event| &event.step) {
+            Some(Step::Compare { index, .. }) => vec![*index],
+            Some(Step::Swap { a, b } | Step::CompareElements { a, b, .. }) => vec![*a, *b],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Records when the elements settled by `frame` and its children reached
+    /// their final position, if that already happened
+    fn settle(&mut self, frame: &Frame) {
+        if self.step >= frame.work.end {
+            for i in frame.settled.iter().flat_map(Range::clone) {
+                let settled = &mut self.settled[i as usize];
+                *settled = Some(settled.map_or(frame.work.end, |step| step.min(frame.work.end)));
+            }
+        }
+        for child in &frame.children {
+            self.settle(child);
+        }
+    }
+
+    /// The labels of the array after the first `step` events
+    fn array_at(&self, step: usize) -> Vec<String> {
+        self.trace
+            .origins_at(step)
+            .into_iter()
+            .map(|origin| self.labels[origin].clone())
+            .collect()
+    }
+
+    pub fn render(&mut self, frame: &Frame) {
+        let mut rows = frame.count();
+        if self.hide_base_cases {
+            rows -= frame.count_base_cases();
+        }
+        let summary = Summary {
+            len: self.labels.len(),
+            widest: self
+                .labels
+                .iter()
+                .map(|label| label.chars().count())
+                .max()
+                .unwrap_or(0),
+            max_depth: frame.max_depth(0),
+            longest_label: longest_label(frame),
+            longest_note: longest_note(frame, self.hide_base_cases),
+            rows,
+        };
+        self.backend.begin(&summary);
+        self.settle(frame);
+        self.render_frame(frame);
+    }
+
+    fn render_frame(&mut self, frame: &Frame) {
+        if self.step <= frame.events.start {
+            return;
+        }
+        let done = self.step >= frame.work.end;
+        let working = !done && self.step > frame.work.start;
+        // the row shows the array before the work, during it and after it
+        let at = if done {
+            frame.work.end
+        } else if working {
+            self.step
+        } else {
+            frame.events.start
+        };
+        let touched = if working { self.touched() } else { Vec::new() };
+        let (mut pivot, mut upper_pivot, mut pivot_source, mut split) = (None, None, None, None);
+        let (mut pruned, mut result) = (Vec::new(), None);
+        match frame.kind {
+            FrameKind::Partition {
+                pivot: ref placed,
+                pivot_source: source,
+            }
+            | FrameKind::Select {
+                pivot: ref placed,
+                pivot_source: source,
+                ..
+            } => {
+                pivot_source = Some(source);
+                if done && placed.is_empty() {
+                    split = Some(placed.start);
+                } else if done {
+                    pivot = Some(placed.clone());
+                }
+            }
+            FrameKind::DualPartition {
+                pivots: [low, high],
+            } if done => {
+                pivot = Some(low..low + 1);
+                upper_pivot = Some(high);
+            }
+            FrameKind::Merge { middle } => split = Some(middle),
+            _ => {}
+        }
+        if let FrameKind::Select {
+            pruned: ref sides,
+            result: found,
+            ..
+        } = frame.kind
+        {
+            if done {
+                pruned = sides.clone();
+                result = found;
+            }
+        }
+        if !(self.hide_base_cases && frame.kind == FrameKind::Base) {
+            let mut row = Row {
+                id: frame.id,
+                label: frame.label.clone(),
+                depth: self.depth,
+                left: frame.left,
+                right: frame.right,
+                active: self.active() == Some(frame.id),
+                cells: Vec::new(),
+                pivot,
+                upper_pivot,
+                split,
+                pivot_source,
+                pruned,
+                result,
+                base_case: frame.base_case(),
+                fallback: frame.fallback(),
+            };
+            row.cells = self.cells(at, &touched, &row);
+            self.backend.row(row);
+        }
+        self.depth += 1;
+        for child in &frame.children {
+            self.render_frame(child);
+        }
+        self.depth -= 1;
+    }
+
+    /// Classifies the cells of the array after `step` events with the
+    /// window and the markers of `row`
+    fn cells(&self, step: usize, touched: &[isize], row: &Row) -> Vec<Cell> {
+        self.array_at(step)
+            .into_iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let settled = self.settled[i].is_some_and(|settled| settled < step);
+                let i = i as isize;
+                // elements settled by earlier calls mostly lie outside the window
+                let state = if settled && !touched.contains(&i) {
+                    CellState::Final
+                } else if i < row.left || i > row.right {
+                    CellState::Outside
+                } else if touched.contains(&i) {
+                    CellState::Touched
+                } else if row.result == Some(i) {
+                    CellState::Result
+                } else if row.pivot.as_ref().is_some_and(|pivot| pivot.contains(&i)) {
+                    CellState::Pivot
+                } else if row.upper_pivot == Some(i) {
+                    CellState::UpperPivot
+                } else if row.pruned.iter().any(|pruned| pruned.contains(&i)) {
+                    CellState::Pruned
+                } else {
+                    CellState::Inside
+                };
+                Cell { label, state }
+            })
+            .collect()
+    }
+
+    pub fn finish(self) -> B::Output {
+        self.backend.finish()
+    }
+}
+
+/// The amount of characters of the longest note in the tree of `frame`,
+/// which for selections includes the result shown once it is found
+fn longest_note(frame: &Frame, hide_base_cases: bool) -> usize {
+    let result = match frame.kind {
+        FrameKind::Select { result, .. } => result,
+        _ => None,
+    };
+    let own = match note(frame.base_case(), frame.fallback(), result) {
+        Some(_) if hide_base_cases && frame.kind == FrameKind::Base => 0,
+        Some(note) => note.chars().count(),
+        None => 0,
+    };
+    frame
+        .children
+        .iter()
+        .map(|child| longest_note(child, hide_base_cases))
+        .fold(own, usize::max)
+}
+
+/// The note shown right of a row: why the recursion stopped, why introsort
+/// fell back or which element a selection found
+fn note(
+    base_case: Option<BaseCase>,
+    fallback: Option<Fallback>,
+    result: Option<isize>,
+) -> Option<String> {
+    base_case
+        .map(|base_case| base_case.reason().to_string())
+        .or(fallback.map(|fallback| fallback.reason().to_string()))
+        .or(result.map(|result| format!("found k = {result}")))
+}
+
+/// The amount of characters of the longest label in the tree of `frame`
+fn longest_label(frame: &Frame) -> usize {
+    frame
+        .children
+        .iter()
+        .map(longest_label)
+        .fold(frame.label.chars().count(), usize::max)
+}
@@ -0,0 +1,463 @@
+use std::fmt::Write;
+
+use leptos::*;
+
+use super::{Backend, Layout, Row, Summary};
+
+/// A primitive of the list view, independent of how it ends up in a document
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Text {
+        x: isize,
+        y: isize,
+        class: String,
+        content: String,
+    },
+    Rect {
+        x: isize,
+        y: isize,
+        width: isize,
+        height: isize,
+        class: &'static str,
+    },
+    Line {
+        x1: isize,
+        y1: isize,
+        x2: isize,
+        y2: isize,
+        class: &'static str,
+    },
+    Ellipse {
+        cx: isize,
+        cy: isize,
+        rx: u32,
+        ry: u32,
+        class: &'static str,
+    },
+}
+
+/// Places the rows of the list view, shared by all SVG backends
+#[derive(Clone, Debug, Default)]
+pub struct Geometry {
+    pub layout: Layout,
+    /// The vertical center of the next row
+    pub y: u32,
+    /// The width of an array column, fitting the widest label
+    pub cell: u32,
+    pub summary: Option<Summary>,
+}
+
+impl Geometry {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            ..Self::default()
+        }
+    }
+
+    pub fn begin(&mut self, summary: &Summary) {
+        self.y = self.layout.row_height / 2;
+        self.cell = self.layout.column_width(summary.widest);
+        self.summary = Some(*summary);
+    }
+
+    /// The horizontal start of the array, right of the deepest call label
+    pub fn array_x(&self) -> isize {
+        let max_depth = self.summary.map_or(0, |summary| summary.max_depth);
+        let layout = &self.layout;
+        (layout.margin + max_depth.saturating_sub(1) as u32 * layout.indent + layout.label_width)
+            as isize
+    }
+
+    /// The horizontal center of the column at `index`
+    pub fn column_x(&self, index: isize) -> isize {
+        self.array_x() + self.cell as isize / 2 + index * self.cell as isize
+    }
+
+    /// The horizontal start of the notes right of the array
+    pub fn note_x(&self, len: usize) -> isize {
+        self.column_x(len as isize) - self.cell as isize / 2 + self.layout.note_padding as isize
+    }
+
+    pub fn width(&self) -> u32 {
+        let (len, longest_note) = self
+            .summary
+            .map_or((0, 0), |summary| (summary.len, summary.longest_note));
+        let layout = &self.layout;
+        let width = self.array_x() as u32
+            + len as u32 * self.cell
+            + layout.note_width(longest_note)
+            + layout.margin;
+        width.max(layout.min_width)
+    }
+
+    pub fn height(&self) -> u32 {
+        let rows = self.summary.map_or(0, |summary| summary.rows);
+        rows as u32 * self.layout.row_height
+    }
+
+    pub fn view_box(&self) -> String {
+        format!("0 0 {} {}", self.width(), self.height())
+    }
+
+    /// The shapes of `row`, placed below the previous row
+    pub fn row(&mut self, row: &Row) -> Vec<Shape> {
+        let layout = self.layout;
+        let cell = self.cell as isize;
+        let y = self.y as isize;
+        let half_row = layout.row_height as isize / 2;
+        let mut shapes = Vec::new();
+        let label_x = layout.margin + row.depth as u32 * layout.indent;
+        let mut label_class = String::from("text");
+        if row.active {
+            label_class += " active";
+        }
+        if row.fallback.is_some() {
+            label_class += " fallback";
+        }
+        shapes.push(Shape::Text {
+            x: label_x as isize,
+            y,
+            class: label_class,
+            content: row.label.clone(),
+        });
+        let window_x = self.column_x(row.left) - cell / 2;
+        shapes.push(if row.right < row.left {
+            Shape::Line {
+                x1: window_x,
+                y1: y - half_row,
+                x2: window_x,
+                y2: y + half_row,
+                class: "window empty",
+            }
+        } else {
+            Shape::Rect {
+                x: window_x,
+                y: y - half_row,
+                width: (row.right - row.left + 1) * cell,
+                height: layout.row_height as isize,
+                class: if row.fallback.is_some() {
+                    "window fallback"
+                } else {
+                    "window"
+                },
+            }
+        });
+        for (i, cell) in row.cells.iter().enumerate() {
+            shapes.push(Shape::Text {
+                x: self.column_x(i as isize),
+                y,
+                class: format!("text anchor-middle {}", cell.state.class()),
+                content: cell.label.clone(),
+            });
+        }
+        if let Some(split) = row.split {
+            let x = self.column_x(split) - cell / 2;
+            let ry = layout.circle_ry() as isize;
+            shapes.push(Shape::Line {
+                x1: x,
+                y1: y - ry,
+                x2: x,
+                y2: y + ry,
+                class: "split",
+            });
+        }
+        if let Some(pivot) = &row.pivot {
+            shapes.extend(pivot.clone().map(|pivot| Shape::Ellipse {
+                cx: self.column_x(pivot),
+                cy: y,
+                rx: layout.circle_rx(self.cell),
+                ry: layout.circle_ry(),
+                class: "circle",
+            }));
+        }
+        if let Some(pivot) = row.upper_pivot {
+            shapes.push(Shape::Ellipse {
+                cx: self.column_x(pivot),
+                cy: y,
+                rx: layout.circle_rx(self.cell),
+                ry: layout.circle_ry(),
+                class: "circle upper",
+            });
+        }
+        for pruned in &row.pruned {
+            shapes.push(Shape::Line {
+                x1: self.column_x(pruned.start) - cell / 2,
+                y1: y,
+                x2: self.column_x(pruned.end) - cell / 2,
+                y2: y,
+                class: "pruned",
+            });
+        }
+        if let Some(result) = row.result {
+            shapes.push(Shape::Rect {
+                x: self.column_x(result) - cell / 2,
+                y: y - half_row,
+                width: cell,
+                height: layout.row_height as isize,
+                class: "result-box",
+            });
+        }
+        if let Some(source) = row.pivot_source {
+            shapes.push(Shape::Ellipse {
+                cx: self.column_x(source),
+                cy: y,
+                rx: layout.source_rx(self.cell),
+                ry: layout.source_ry(),
+                class: "pivot-source",
+            });
+        }
+        if let Some(note) = row.note() {
+            shapes.push(Shape::Text {
+                x: self.note_x(row.cells.len()),
+                y,
+                class: if row.fallback.is_some() {
+                    "text note fallback"
+                } else {
+                    "text note"
+                }
+                .to_string(),
+                content: note,
+            });
+        }
+        self.y += layout.row_height;
+        shapes
+    }
+}
+
+/// Draws the rows into an SVG view, indented by recursion depth
+#[derive(Default)]
+pub struct SvgBackend {
+    pub geometry: Geometry,
+    pub children: Vec<View>,
+}
+
+impl SvgBackend {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            geometry: Geometry::new(layout),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Backend for SvgBackend {
+    type Output = View;
+
+    fn begin(&mut self, summary: &Summary) {
+        self.geometry.begin(summary);
+    }
+
+    fn row(&mut self, row: Row) {
+        let shapes = self.geometry.row(&row);
+        self.children
+            .extend(shapes.into_iter().map(|shape| match shape {
+                Shape::Text {
+                    x,
+                    y,
+                    class,
+                    content,
+                } => view! { <text x=x y=y class=class>{content}</text> }.into_view(),
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    class,
+                } => view! { <rect x=x y=y width=width height=height class=class /> }.into_view(),
+                Shape::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    class,
+                } => view! { <line x1=x1 y1=y1 x2=x2 y2=y2 class=class /> }.into_view(),
+                Shape::Ellipse {
+                    cx,
+                    cy,
+                    rx,
+                    ry,
+                    class,
+                } => view! { <ellipse cx=cx cy=cy rx=rx ry=ry class=class /> }.into_view(),
+            }));
+    }
+
+    fn finish(self) -> View {
+        let font_size = format!("{}px", self.geometry.layout.font_size);
+        view! {
+            <svg viewBox=self.geometry.view_box() style:font-size=font_size>
+                {self.children}
+            </svg>
+        }
+        .into_view()
+    }
+}
+
+/// Writes the rows as a standalone SVG document with style.css inlined,
+/// which works without a browser
+#[derive(Default)]
+pub struct StaticSvgBackend {
+    pub geometry: Geometry,
+    pub body: String,
+}
+
+impl StaticSvgBackend {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            geometry: Geometry::new(layout),
+            body: String::new(),
+        }
+    }
+}
+
+/// Escapes text for XML content and attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Backend for StaticSvgBackend {
+    type Output = String;
+
+    fn begin(&mut self, summary: &Summary) {
+        self.geometry.begin(summary);
+    }
+
+    fn row(&mut self, row: Row) {
+        for shape in self.geometry.row(&row) {
+            // writing into a `String` never fails
+            let _ = match shape {
+                Shape::Text {
+                    x,
+                    y,
+                    class,
+                    content,
+                } => writeln!(
+                    self.body,
+                    r#"<text x="{x}" y="{y}" class="{class}">{}</text>"#,
+                    escape(&content)
+                ),
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    class,
+                } => writeln!(
+                    self.body,
+                    r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" class="{class}"/>"#
+                ),
+                Shape::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    class,
+                } => writeln!(
+                    self.body,
+                    r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" class="{class}"/>"#
+                ),
+                Shape::Ellipse {
+                    cx,
+                    cy,
+                    rx,
+                    ry,
+                    class,
+                } => writeln!(
+                    self.body,
+                    r#"<ellipse cx="{cx}" cy="{cy}" rx="{rx}" ry="{ry}" class="{class}"/>"#
+                ),
+            };
+        }
+    }
+
+    fn finish(self) -> String {
+        let geometry = &self.geometry;
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{}" width="{}" height="{}" style="font-size: {}px">"#,
+                "\n<style>\n{}</style>\n{}</svg>\n"
+            ),
+            geometry.view_box(),
+            geometry.width(),
+            geometry.height(),
+            geometry.layout.font_size,
+            include_str!("../style.css"),
+            self.body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithm::{sort, Algorithm, SortOptions},
+        choice::Choice,
+        element::{ElementKind, SortOrder},
+        input::parse_array,
+        render::{Density, Renderer},
+    };
+
+    /// Collects the shapes of every row
+    struct Shapes {
+        geometry: Geometry,
+        shapes: Vec<Shape>,
+    }
+
+    impl Backend for Shapes {
+        type Output = Self;
+
+        fn begin(&mut self, summary: &Summary) {
+            self.geometry.begin(summary);
+        }
+
+        fn row(&mut self, row: Row) {
+            let shapes = self.geometry.row(&row);
+            self.shapes.extend(shapes);
+        }
+
+        fn finish(self) -> Self {
+            self
+        }
+    }
+
+    #[test]
+    fn view_box_covers_the_notes() {
+        let mut array = parse_array("5 4 3 2 1 9 8 7 6 0", ElementKind::Integer).unwrap();
+        let options = SortOptions {
+            algorithm: Algorithm::IntroSort,
+            depth_limit: 1,
+            ..SortOptions::default()
+        };
+        let trace = sort(&mut array, options, SortOrder::Ascending).unwrap();
+        for &density in Density::ALL {
+            let layout = Layout::new(density);
+            let backend = Shapes {
+                geometry: Geometry::new(layout),
+                shapes: Vec::new(),
+            };
+            let mut renderer = Renderer::new(&trace, trace.events().len(), backend);
+            renderer.render(&trace.root);
+            let Shapes { geometry, shapes } = renderer.finish();
+            let notes = shapes.iter().filter_map(|shape| match shape {
+                Shape::Text {
+                    x, class, content, ..
+                } if class.contains("note") => {
+                    Some(*x as u32 + content.chars().count() as u32 * layout.char_width)
+                }
+                _ => None,
+            });
+            assert!(notes.clone().count() > 0);
+            for end in notes {
+                assert!(
+                    end <= geometry.width(),
+                    "{density}: {end} > {}",
+                    geometry.width()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use leptos::*;
+
+use super::{Backend, CellState, Row, Summary};
+
+/// Lays the rows out as an HTML `<table>`, which screen readers can
+/// navigate and which can be copied into spreadsheets
+#[derive(Default)]
+pub struct TableBackend {
+    pub len: usize,
+    pub rows: Vec<View>,
+}
+
+impl TableBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Backend for TableBackend {
+    type Output = View;
+
+    fn begin(&mut self, summary: &Summary) {
+        self.len = summary.len;
+    }
+
+    fn row(&mut self, row: Row) {
+        let cells = row
+            .cells
+            .iter()
+            .map(|cell| {
+                let title = match cell.state {
+                    CellState::Outside => "outside of the range",
+                    CellState::Inside => "",
+                    CellState::Touched => "compared or swapped",
+                    CellState::Pivot => "pivot",
+                    CellState::UpperPivot => "larger pivot",
+                    CellState::Result => "selected element",
+                    CellState::Pruned => "pruned, not visited",
+                    CellState::Final => "in its final position",
+                };
+                view! {
+                    <td class=cell.state.class() title=title>{cell.label.clone()}</td>
+                }
+            })
+            .collect_view();
+        let note = row.note();
+        self.rows.push(
+            view! {
+                <tr class:active=row.active class:fallback=row.fallback.is_some()>
+                    <th scope="row" style:padding-left=format!("{}em", row.depth)>{row.label.clone()}</th>
+                    {cells}
+                    <td class="note">{note}</td>
+                </tr>
+            }
+            .into_view(),
+        );
+    }
+
+    fn finish(self) -> View {
+        view! {
+            <div class="table-container">
+                <table class="trace-table">
+                    <thead>
+                        <tr>
+                            <th scope="col">"Call"</th>
+                            {(0..self.len).map(|i| view! { <th scope="col">{i}</th> }).collect_view()}
+                            <th scope="col"></th>
+                        </tr>
+                    </thead>
+                    <tbody>{self.rows}</tbody>
+                </table>
+            </div>
+        }
+        .into_view()
+    }
+}
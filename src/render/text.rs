@@ -0,0 +1,104 @@
+use super::{Backend, CellState, Row, Summary};
+
+/// Writes the rows as plain text, one line per call
+///
+/// The range of a call is enclosed in `|` and split by `|` where it is
+/// halved, the pivot is marked as `(x)`, the larger of two pivots as `<x>`,
+/// elements in their final position as `[x]`, elements touched by the last
+/// event as `!x!`, sides pruned by a selection as `~x~` and the selected
+/// element as `*x*`.
+#[derive(Default)]
+pub struct TextBackend {
+    pub lines: Vec<String>,
+    pub widest: usize,
+    pub label_width: usize,
+}
+
+impl TextBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Backend for TextBackend {
+    type Output = String;
+
+    fn begin(&mut self, summary: &Summary) {
+        self.widest = summary.widest;
+        self.label_width = summary.max_depth * 2 + summary.longest_label;
+    }
+
+    fn row(&mut self, row: Row) {
+        let mut line = format!("{}{}", "  ".repeat(row.depth), row.label);
+        line = format!("{line:<width$}", width = self.label_width);
+        let width = self.widest;
+        let split = row.split;
+        for (i, cell) in row.cells.iter().enumerate() {
+            let i = i as isize;
+            let boundary = i == row.left || i == row.right + 1 || split == Some(i);
+            line.push(if boundary { '|' } else { ' ' });
+            let label = format!("{:>width$}", cell.label);
+            line += &match cell.state {
+                CellState::Touched => format!("!{label}!"),
+                CellState::Pivot => format!("({label})"),
+                CellState::UpperPivot => format!("<{label}>"),
+                CellState::Result => format!("*{label}*"),
+                CellState::Pruned => format!("~{label}~"),
+                CellState::Final => format!("[{label}]"),
+                CellState::Outside | CellState::Inside => format!(" {label} "),
+            };
+        }
+        let end = row.cells.len() as isize;
+        line.push(if row.right + 1 == end || row.left == end {
+            '|'
+        } else {
+            ' '
+        });
+        if let Some(note) = row.note() {
+            line += &format!("  -- {note}");
+        }
+        self.lines.push(line.trim_end().to_string());
+    }
+
+    fn finish(self) -> String {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithm::{sort, SortOptions},
+        element::{ElementKind, SortOrder},
+        input::parse_array,
+        render::Renderer,
+    };
+
+    #[test]
+    fn renders_the_default_array() {
+        let mut array = parse_array("3 5 2 7 8 6 1 9 3 4", ElementKind::Integer).unwrap();
+        let trace = sort(&mut array, SortOptions::default(), SortOrder::Ascending).unwrap();
+        let mut renderer = Renderer::new(&trace, trace.events().len(), TextBackend::new());
+        renderer.render(&trace.root);
+        let text = renderer.finish();
+        let expected = [
+            "qS(0, 9, ...)           | 3   2   1   3  (4)  6   5   9   7   8 |",
+            "  qS(0, 3, ...)         | 2   1  (3)  3 |[4]  6   5   9   7   8",
+            "    qS(0, 1, ...)       |(1)  2 |[3]  3  [4]  6   5   9   7   8",
+            "      qS(0, -1, ...)    |[1]  2  [3]  3  [4]  6   5   9   7   8    -- empty range",
+            "      qS(1, 1, ...)      [1]| 2 |[3]  3  [4]  6   5   9   7   8    -- single element",
+            "    qS(3, 3, ...)        [1] [2] [3]| 3 |[4]  6   5   9   7   8    -- single element",
+            "  qS(5, 9, ...)          [1] [2] [3] [3] [4]| 6   5   7  (8)  9 |",
+            "    qS(5, 7, ...)        [1] [2] [3] [3] [4]| 6   5  (7)|[8]  9",
+            "      qS(5, 6, ...)      [1] [2] [3] [3] [4]|(5)  6 |[7] [8]  9",
+            "        qS(5, 4, ...)    [1] [2] [3] [3] [4]|[5]  6  [7] [8]  9    -- empty range",
+            "        qS(6, 6, ...)    [1] [2] [3] [3] [4] [5]| 6 |[7] [8]  9    -- single element",
+            "      qS(8, 7, ...)      [1] [2] [3] [3] [4] [5] [6] [7]|[8]  9    -- empty range",
+            "    qS(9, 9, ...)        [1] [2] [3] [3] [4] [5] [6] [7] [8]| 9 |  -- single element",
+        ];
+        assert_eq!(text.lines().collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,66 @@
+use leptos::*;
+use quicksort::trace::Trace;
+
+/// Lists the ranges pending on the explicit stack of an iterative sort, top
+/// first, next to the ⌊log₂ n⌋ + 1 ranges it needs at most when the smaller
+/// side is sorted first
+#[component]
+pub fn StackPanel<T>(
+    #[prop(into)] trace: Signal<Trace<T>>,
+    /// The amount of events which already happened
+    #[prop(into)]
+    step: Signal<usize>,
+    /// Names the run when several are shown
+    #[prop(optional, into)]
+    title: MaybeProp<&'static str>,
+) -> impl IntoView
+where
+    T: 'static,
+{
+    let stack = create_memo(move |_| trace.with(|trace| trace.stack_at(step())));
+    let max_stack = create_memo(move |_| trace.with(|trace| trace.stats_at(step()).max_stack));
+    let bound = move || {
+        let len = trace.with(|trace| trace.input.len());
+        len.checked_ilog2().map_or(0, |log| log as usize + 1)
+    };
+    let over = move || max_stack() > bound();
+    let ranges = move || {
+        stack.with(|stack| {
+            stack
+                .iter()
+                .rev()
+                .map(|&(left, right)| {
+                    let len = (right - left + 1).max(0);
+                    view! {
+                        <li>
+                            {format!("({left}, {right})")}
+                            <span class="note">{format!(" {len} elements")}</span>
+                        </li>
+                    }
+                })
+                .collect_view()
+        })
+    };
+    view! {
+        <div class="stack-panel">
+            <h3>{move || title.get().map_or("Stack".to_string(), |title| format!("{title}: stack"))}</h3>
+            <table>
+                <tbody>
+                    <tr>
+                        <th scope="row">"Pending"</th>
+                        <td>{move || stack.with(Vec::len)}</td>
+                    </tr>
+                    <tr>
+                        <th scope="row">"Max so far"</th>
+                        <td class:over=over>{max_stack}</td>
+                    </tr>
+                    <tr>
+                        <th scope="row">"⌊log₂n⌋ + 1"</th>
+                        <td>{bound}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <ol class="stack">{ranges}</ol>
+        </div>
+    }
+}
@@ -0,0 +1,168 @@
+use leptos::*;
+use quicksort::trace::{Stats, Trace};
+
+const WIDTH: f64 = 240.0;
+const HEIGHT: f64 = 160.0;
+/// The space left of and below the plot for the axis labels
+const MARGIN: f64 = 30.0;
+/// The amount of points of each reference curve
+const SAMPLES: usize = 40;
+
+fn n_log_n(n: f64) -> f64 {
+    if n > 1.0 {
+        n * n.log2()
+    } else {
+        0.0
+    }
+}
+
+fn n_squared(n: f64) -> f64 {
+    n * n
+}
+
+/// The points of `f` over `0.0..=n` as a `<polyline>` attribute
+fn curve(n: f64, y_max: f64, f: fn(f64) -> f64) -> String {
+    (0..=SAMPLES)
+        .map(|i| {
+            let x = n * i as f64 / SAMPLES as f64;
+            format!("{:.1},{:.1}", plot_x(x, n), plot_y(f(x), y_max))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn plot_x(x: f64, n: f64) -> f64 {
+    MARGIN + x / n.max(1.0) * (WIDTH - MARGIN - 10.0)
+}
+
+fn plot_y(y: f64, y_max: f64) -> f64 {
+    HEIGHT - MARGIN - y / y_max.max(1.0) * (HEIGHT - MARGIN - 10.0)
+}
+
+/// Counts the work of a trace up to the current step and compares it with
+/// the growth of n·log₂n and n²
+#[component]
+pub fn StatsPanel<T>(
+    #[prop(into)] trace: Signal<Trace<T>>,
+    /// The amount of events which already happened
+    #[prop(into)]
+    step: Signal<usize>,
+) -> impl IntoView
+where
+    T: 'static,
+{
+    let current = create_memo(move |_| trace.with(|trace| trace.stats_at(step())));
+    let total = create_memo(move |_| trace.with(|trace| trace.stats_at(usize::MAX)));
+    let n = move || trace.with(|trace| trace.input.len()) as f64;
+    // the plot fits n² and the comparisons, which may exceed it for tiny arrays
+    let y_max = move || n_squared(n()).max(total().comparisons as f64);
+    let row = move |name: &'static str, count: fn(&Stats) -> usize| {
+        view! {
+            <tr>
+                <th scope="row">{name}</th>
+                <td>{move || current.with(count)}</td>
+                <td>{move || total.with(count)}</td>
+            </tr>
+        }
+    };
+    view! {
+        <div class="stats">
+            <table>
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th scope="col">"Now"</th>
+                        <th scope="col">"Total"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {row("Comparisons", |stats| stats.comparisons)}
+                    {row("Swaps", |stats| stats.swaps)}
+                    {row("Calls", |stats| stats.calls)}
+                    {row("Max depth", |stats| stats.max_depth)}
+                    {row("Max stack", |stats| stats.max_stack)}
+                </tbody>
+            </table>
+            <svg viewBox=format!("0 0 {WIDTH} {HEIGHT}")>
+                <line x1=MARGIN y1=(HEIGHT - MARGIN) x2=WIDTH y2=(HEIGHT - MARGIN) class="axis"/>
+                <line x1=MARGIN y1=0 x2=MARGIN y2=(HEIGHT - MARGIN) class="axis"/>
+                <text x=(WIDTH - 10.0) y=(HEIGHT - 10.0) class="axis-label anchor-middle">
+                    {move || format!("n = {}", n())}
+                </text>
+                <polyline points=move || curve(n(), y_max(), n_squared) class="curve n-squared"/>
+                <polyline points=move || curve(n(), y_max(), n_log_n) class="curve n-log-n"/>
+                <circle
+                    cx=move || plot_x(n(), n())
+                    cy=move || plot_y(current().comparisons as f64, y_max())
+                    r=4
+                    class="marker comparisons"
+                />
+                <circle
+                    cx=move || plot_x(n(), n())
+                    cy=move || plot_y(current().swaps as f64, y_max())
+                    r=4
+                    class="marker swaps"
+                />
+            </svg>
+            <ul class="legend">
+                <li class="n-squared">{move || format!("n² = {}", n_squared(n()))}</li>
+                <li class="n-log-n">{move || format!("n·log₂n ≈ {:.0}", n_log_n(n()))}</li>
+                <li class="comparisons">"comparisons"</li>
+                <li class="swaps">"swaps"</li>
+                <li>{move || format!("log₂n ≈ {:.1} for the depth", n().log2().max(0.0))}</li>
+            </ul>
+        </div>
+    }
+}
+
+/// Compares the work of two traces of the same input up to the current step
+#[component]
+pub fn StatsDiff<T>(
+    #[prop(into)] a: Signal<Trace<T>>,
+    #[prop(into)] b: Signal<Trace<T>>,
+    /// The amount of events which already happened in both traces
+    #[prop(into)]
+    step: Signal<usize>,
+) -> impl IntoView
+where
+    T: 'static,
+{
+    let stats_a = create_memo(move |_| a.with(|trace| trace.stats_at(step())));
+    let stats_b = create_memo(move |_| b.with(|trace| trace.stats_at(step())));
+    let row = move |name: &'static str, count: fn(&Stats) -> usize| {
+        let diff = move || stats_b.with(count) as isize - stats_a.with(count) as isize;
+        let fewer = move || diff().is_negative();
+        let more = move || diff().is_positive();
+        view! {
+            <tr>
+                <th scope="row">{name}</th>
+                <td>{move || stats_a.with(count)}</td>
+                <td>{move || stats_b.with(count)}</td>
+                <td class:fewer=fewer class:more=more>
+                    {move || format!("{:+}", diff())}
+                </td>
+            </tr>
+        }
+    };
+    view! {
+        <div class="stats">
+            <table>
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th scope="col">"A"</th>
+                        <th scope="col">"B"</th>
+                        <th scope="col">"B − A"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {row("Comparisons", |stats| stats.comparisons)}
+                    {row("Swaps", |stats| stats.swaps)}
+                    {row("Calls", |stats| stats.calls)}
+                    {row("Max depth", |stats| stats.max_depth)}
+                    {row("Max stack", |stats| stats.max_stack)}
+                </tbody>
+            </table>
+        </div>
+    }
+}
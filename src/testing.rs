@@ -0,0 +1,35 @@
+//! Inputs and checks shared by the tests of the algorithms
+
+use crate::{choice::Choice, element::SortOrder, trace::Trace};
+
+/// Arrays with the usual trouble spots: none or one element, duplicates,
+/// sorted and reversed runs and negative numbers
+pub const ARRAYS: [&[i64]; 8] = [
+    &[],
+    &[1],
+    &[3, 5, 2, 7, 8, 6, 1, 9, 3, 4],
+    &[1, 2, 3, 4, 5, 6, 7, 8],
+    &[8, 7, 6, 5, 4, 3, 2, 1],
+    &[4, 4, 4, 4, 4, 4],
+    &[2, 9, 2, 0, 9, 5, 2, 0, 0, 7, 5, 9, 2],
+    &[-3, 12, -40, 0, 7, -3, 105, -1],
+];
+
+/// Checks that `sort` sorts every array of [`ARRAYS`] in both orders, in
+/// place as well as in the last state of its trace
+pub fn assert_sorts(name: &str, mut sort: impl FnMut(&mut [i64], SortOrder) -> Trace<i64>) {
+    for &order in SortOrder::ALL {
+        for input in ARRAYS {
+            let mut array = input.to_vec();
+            let trace = sort(&mut array, order);
+            let mut sorted = input.to_vec();
+            sorted.sort_by(|a, b| order.compare(a, b));
+            assert_eq!(array, sorted, "{name} {order} {input:?}");
+            assert_eq!(
+                trace.array_at(trace.events().len()),
+                sorted,
+                "{name} {order} {input:?}"
+            );
+        }
+    }
+}
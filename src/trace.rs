@@ -0,0 +1,514 @@
+use std::{cmp::Ordering, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Frame, FrameKind};
+
+/// A single step of a sort run
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Step {
+    /// A call on `left..=right` begins
+    Recurse { left: isize, right: isize },
+    /// The element at `index` is chosen as pivot
+    ChoosePivot { index: isize },
+    /// The element at `index` is compared with the pivot
+    Compare {
+        index: isize,
+        #[serde(with = "ordering")]
+        ordering: Ordering,
+    },
+    /// The elements at `a` and `b` are compared while choosing the pivot
+    CompareElements {
+        a: isize,
+        b: isize,
+        #[serde(with = "ordering")]
+        ordering: Ordering,
+    },
+    /// The left scanning index moves forward to `index`
+    Advance { index: isize },
+    /// The right scanning index moves backward to `index`
+    Retreat { index: isize },
+    /// The elements at `a` and `b` are swapped
+    Swap { a: isize, b: isize },
+    /// The pivot ended up in its final position `range`
+    PlacePivot { range: Range<isize> },
+    /// `left..=right` is pushed onto the explicit stack of an iterative sort
+    PushRange { left: isize, right: isize },
+    /// The topmost range is popped off the explicit stack
+    PopRange,
+    /// The call returns
+    Return,
+}
+
+/// A [`Step`] together with the id of the frame it belongs to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub frame: usize,
+    pub step: Step,
+}
+
+/// The work done by a sort run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Comparisons of two elements, including the ones choosing the pivot
+    pub comparisons: usize,
+    pub swaps: usize,
+    /// Calls including base cases, which is [`Frame::count`] for a whole run
+    pub calls: usize,
+    /// The deepest nesting of calls, which is [`Frame::max_depth`] for a
+    /// whole run
+    pub max_depth: usize,
+    /// The most ranges pending at once on the explicit stack of an iterative
+    /// sort
+    pub max_stack: usize,
+}
+
+/// Seeking never replays more than this many events, or the length of the
+/// array if it is larger
+const MIN_CHECKPOINT_INTERVAL: usize = 256;
+
+/// The full trace of a sort run
+///
+/// Only the input is stored in full, every later state of the array is
+/// reconstructed from the swaps starting at the closest checkpoint.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Trace<T> {
+    /// The array before sorting
+    pub input: Vec<T>,
+    /// The root frame of the recursion
+    #[serde(with = "frames")]
+    pub root: Frame,
+    /// All events in the order they happened, private because the
+    /// checkpoints are derived from them
+    events: Vec<Event>,
+    /// The amount of events between two checkpoints
+    #[serde(skip)]
+    checkpoint_interval: usize,
+    /// The input index of every element after each multiple of
+    /// `checkpoint_interval` events
+    #[serde(skip)]
+    checkpoints: Vec<Vec<usize>>,
+}
+
+/// The serialized fields of a [`Trace`], which are validated before the
+/// checkpoints are rebuilt from them
+#[derive(Deserialize)]
+pub struct RawTrace<T> {
+    pub input: Vec<T>,
+    #[serde(with = "frames")]
+    pub root: Frame,
+    pub events: Vec<Event>,
+}
+
+/// Applies the swaps of `events` to `array`
+pub fn replay<T>(events: &[Event], array: &mut [T]) {
+    for event in events {
+        if let Step::Swap { a, b } = event.step {
+            array.swap(a as usize, b as usize);
+        }
+    }
+}
+
+impl<T> Trace<T> {
+    pub fn new(input: Vec<T>, root: Frame, events: Vec<Event>) -> Self {
+        let checkpoint_interval = input.len().max(MIN_CHECKPOINT_INTERVAL);
+        let mut origins: Vec<usize> = (0..input.len()).collect();
+        let mut checkpoints = vec![origins.clone()];
+        for chunk in events.chunks(checkpoint_interval) {
+            replay(chunk, &mut origins);
+            checkpoints.push(origins.clone());
+        }
+        Self {
+            input,
+            root,
+            events,
+            checkpoint_interval,
+            checkpoints,
+        }
+    }
+
+    /// All events in the order they happened
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Reconstructs the array after the first `step` events
+    pub fn array_at(&self, step: usize) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.origins_at(step)
+            .into_iter()
+            .map(|origin| self.input[origin].clone())
+            .collect()
+    }
+
+    /// Counts the work done in the first `step` events
+    pub fn stats_at(&self, step: usize) -> Stats {
+        let mut stats = Stats::default();
+        let mut depth = 0;
+        let mut stack = 0;
+        for event in &self.events[..step.min(self.events.len())] {
+            match event.step {
+                Step::Compare { .. } | Step::CompareElements { .. } => stats.comparisons += 1,
+                Step::Swap { .. } => stats.swaps += 1,
+                Step::Recurse { .. } => {
+                    stats.calls += 1;
+                    depth += 1;
+                    stats.max_depth = stats.max_depth.max(depth);
+                }
+                Step::Return => depth -= 1,
+                Step::PushRange { .. } => {
+                    stack += 1;
+                    stats.max_stack = stats.max_stack.max(stack);
+                }
+                Step::PopRange => stack -= 1,
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// The `(left, right)` ranges pending on the explicit stack after the
+    /// first `step` events, from the bottom to the top
+    pub fn stack_at(&self, step: usize) -> Vec<(isize, isize)> {
+        let mut stack = Vec::new();
+        for event in &self.events[..step.min(self.events.len())] {
+            match event.step {
+                Step::PushRange { left, right } => stack.push((left, right)),
+                Step::PopRange => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+        stack
+    }
+
+    /// The input index of every element after the first `step` events
+    pub fn origins_at(&self, step: usize) -> Vec<usize> {
+        let step = step.min(self.events.len());
+        let checkpoint = step / self.checkpoint_interval;
+        let mut origins = self.checkpoints[checkpoint].clone();
+        replay(
+            &self.events[checkpoint * self.checkpoint_interval..step],
+            &mut origins,
+        );
+        origins
+    }
+}
+
+/// Records the events of the frame which is currently running
+#[derive(Default)]
+pub struct Tracer {
+    pub frame: usize,
+    pub events: Vec<Event>,
+    /// The amount of frames entered so far
+    pub frame_count: usize,
+}
+
+/// A frame which was entered but did not return yet
+#[derive(Clone, Copy, Debug)]
+pub struct Entered {
+    pub id: usize,
+    pub left: isize,
+    pub right: isize,
+    /// The id of the frame to return to
+    pub parent: usize,
+    /// The index of the event which entered the frame
+    pub start: usize,
+}
+
+impl Tracer {
+    /// Enters a new frame on `left..=right`
+    pub fn enter(&mut self, left: isize, right: isize) -> Entered {
+        let entered = Entered {
+            id: self.frame_count,
+            left,
+            right,
+            parent: self.frame,
+            start: self.events.len(),
+        };
+        self.frame_count += 1;
+        self.frame = entered.id;
+        self.push(Step::Recurse { left, right });
+        entered
+    }
+
+    /// Returns from `entered` and builds its frame
+    pub fn exit(
+        &mut self,
+        entered: Entered,
+        label: String,
+        work: Range<usize>,
+        settled: Vec<Range<isize>>,
+        kind: FrameKind,
+        children: Vec<Frame>,
+    ) -> Frame {
+        self.push(Step::Return);
+        self.frame = entered.parent;
+        Frame {
+            id: entered.id,
+            label,
+            left: entered.left,
+            right: entered.right,
+            events: entered.start..self.events.len(),
+            work,
+            settled,
+            kind,
+            children,
+        }
+    }
+
+    /// Runs `work` as a [`FrameKind::Pass`] on `left..=right`
+    pub fn pass(
+        &mut self,
+        left: isize,
+        right: isize,
+        label: String,
+        settled: Vec<Range<isize>>,
+        work: impl FnOnce(&mut Self),
+    ) -> Frame {
+        let entered = self.enter(left, right);
+        let start = self.events.len();
+        work(self);
+        let work = start..self.events.len();
+        self.exit(entered, label, work, settled, FrameKind::Pass, Vec::new())
+    }
+
+    /// Runs the passes of an iterative sort on `left..=right` below a
+    /// [`FrameKind::Sequence`], or a base case if there is nothing to sort
+    pub fn sequence(
+        &mut self,
+        left: isize,
+        right: isize,
+        label: String,
+        passes: impl FnOnce(&mut Self) -> Vec<Frame>,
+    ) -> Frame {
+        let entered = self.enter(left, right);
+        let work = entered.start..entered.start + 1;
+        if right <= left {
+            let settled = vec![left..right + 1];
+            return self.exit(entered, label, work, settled, FrameKind::Base, Vec::new());
+        }
+        let children = passes(self);
+        self.exit(
+            entered,
+            label,
+            work,
+            Vec::new(),
+            FrameKind::Sequence,
+            children,
+        )
+    }
+
+    pub fn push(&mut self, step: Step) {
+        self.events.push(Event {
+            frame: self.frame,
+            step,
+        });
+    }
+
+    /// Compares the element at `index` with the pivot
+    pub fn compare<T>(
+        &mut self,
+        array: &[T],
+        index: isize,
+        pivot: &T,
+        compare: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> Ordering {
+        let ordering = compare(&array[index as usize], pivot);
+        self.push(Step::Compare { index, ordering });
+        ordering
+    }
+
+    /// Compares the elements at `a` and `b`
+    pub fn compare_elements<T>(
+        &mut self,
+        array: &[T],
+        a: isize,
+        b: isize,
+        compare: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> Ordering {
+        let ordering = compare(&array[a as usize], &array[b as usize]);
+        self.push(Step::CompareElements { a, b, ordering });
+        ordering
+    }
+
+    /// Swaps the elements at `a` and `b`
+    ///
+    /// Swapping an element with itself moves nothing, so it is not recorded
+    /// and does not count as a swap.
+    pub fn swap<T>(&mut self, array: &mut [T], a: isize, b: isize) {
+        if a != b {
+            array.swap(a as usize, b as usize);
+            self.push(Step::Swap { a, b });
+        }
+    }
+
+    /// Rearranges `array[left..]` so that its `i`-th element is the one
+    /// which was at `left + order[i]`, with at most one swap per element
+    pub fn permute<T>(&mut self, array: &mut [T], left: isize, order: &[usize]) {
+        // the current position of every element which still has to move
+        let mut position: Vec<usize> = (0..order.len()).collect();
+        let mut occupant: Vec<usize> = (0..order.len()).collect();
+        for (target, &origin) in order.iter().enumerate() {
+            let source = position[origin];
+            if source != target {
+                self.swap(array, left + target as isize, left + source as isize);
+                let displaced = occupant[target];
+                position[displaced] = source;
+                occupant[source] = displaced;
+            }
+            position[origin] = target;
+            occupant[target] = origin;
+        }
+    }
+}
+
+/// Serializes a [`Frame`] tree as a flat list in call order, where every
+/// frame is followed by the subtrees of its children
+///
+/// Unlike nested objects this keeps deep recursions below the nesting limit of
+/// serde_json, which rejects more than 128 levels.
+mod frames {
+    use std::ops::Range;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{Frame, FrameKind};
+
+    #[derive(Serialize)]
+    struct FlatFrameRef<'a> {
+        id: usize,
+        label: &'a str,
+        left: isize,
+        right: isize,
+        events: &'a Range<usize>,
+        work: &'a Range<usize>,
+        settled: &'a [Range<isize>],
+        kind: &'a FrameKind,
+        /// The amount of children
+        children: usize,
+    }
+
+    #[derive(Deserialize)]
+    struct FlatFrame {
+        id: usize,
+        label: String,
+        left: isize,
+        right: isize,
+        events: Range<usize>,
+        work: Range<usize>,
+        settled: Vec<Range<isize>>,
+        kind: FrameKind,
+        children: usize,
+    }
+
+    pub fn serialize<S: Serializer>(root: &Frame, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut flat = Vec::new();
+        let mut stack = vec![root];
+        while let Some(frame) = stack.pop() {
+            flat.push(FlatFrameRef {
+                id: frame.id,
+                label: &frame.label,
+                left: frame.left,
+                right: frame.right,
+                events: &frame.events,
+                work: &frame.work,
+                settled: &frame.settled,
+                kind: &frame.kind,
+                children: frame.children.len(),
+            });
+            stack.extend(frame.children.iter().rev());
+        }
+        serializer.collect_seq(flat)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Frame, D::Error> {
+        let flat = Vec::<FlatFrame>::deserialize(deserializer)?;
+        // the subtrees are built from the back, so the first child is on top
+        let mut built: Vec<Frame> = Vec::new();
+        for frame in flat.into_iter().rev() {
+            if frame.children > built.len() {
+                return Err(D::Error::custom(format!(
+                    "frame {} is missing some of its {} children",
+                    frame.id, frame.children
+                )));
+            }
+            let children = built.split_off(built.len() - frame.children);
+            built.push(Frame {
+                id: frame.id,
+                label: frame.label,
+                left: frame.left,
+                right: frame.right,
+                events: frame.events,
+                work: frame.work,
+                settled: frame.settled,
+                kind: frame.kind,
+                children: children.into_iter().rev().collect(),
+            });
+        }
+        match <[Frame; 1]>::try_from(built) {
+            Ok([root]) => Ok(root),
+            Err(built) => Err(D::Error::custom(format!(
+                "expected a single root frame, found {}",
+                built.len()
+            ))),
+        }
+    }
+}
+
+/// Serializes an [`Ordering`] as `"less"`, `"equal"` or `"greater"`
+mod ordering {
+    use std::cmp::Ordering;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ordering: &Ordering, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match ordering {
+            Ordering::Less => "less",
+            Ordering::Equal => "equal",
+            Ordering::Greater => "greater",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ordering, D::Error> {
+        match <&str>::deserialize(deserializer)? {
+            "less" => Ok(Ordering::Less),
+            "equal" => Ok(Ordering::Equal),
+            "greater" => Ok(Ordering::Greater),
+            other => Err(D::Error::unknown_variant(
+                other,
+                &["less", "equal", "greater"],
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{algorithm::SortOptions, quick_sort::quick_sort};
+
+    #[test]
+    fn checkpoints_match_replaying_the_events() {
+        for len in [100, 300] {
+            let input: Vec<i64> = (0..len).rev().collect();
+            let mut array = input.clone();
+            let trace = quick_sort(&mut array, 0, len as isize - 1, SortOptions::default());
+            let events = trace.events();
+            let interval = trace.checkpoint_interval;
+            assert!(events.len() > 2 * interval, "{len}: {}", events.len());
+            let boundaries = (0..=events.len() / interval).map(|checkpoint| checkpoint * interval);
+            for step in boundaries.flat_map(|step| [step.saturating_sub(1), step, step + 1]) {
+                let step = step.min(events.len());
+                let mut replayed = input.clone();
+                replay(&events[..step], &mut replayed);
+                assert_eq!(trace.array_at(step), replayed, "{len}: {step}");
+            }
+            assert_eq!(trace.array_at(usize::MAX), array);
+        }
+    }
+}
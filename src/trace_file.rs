@@ -0,0 +1,1000 @@
+use std::{fmt, ops::Range};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    algorithm::SortOptions,
+    element::{Element, ElementKind, SortOrder},
+    input::parse_array,
+    trace::{Event, RawTrace, Step, Trace},
+    Frame, FrameKind,
+};
+
+/// The version of the trace file format written by this build
+///
+/// Older versions are migrated when they are read: versions 1 and 2 only knew
+/// quicksort frames, version 3 nested the frames and settled a single range
+/// per frame.
+pub const VERSION: u32 = 4;
+
+/// A sort run together with everything needed to reproduce it
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TraceFile {
+    pub version: u32,
+    pub kind: ElementKind,
+    pub order: SortOrder,
+    pub options: SortOptions,
+    pub trace: Trace<Element>,
+}
+
+/// A [`TraceFile`] as read, before it is validated
+#[derive(Deserialize)]
+struct RawTraceFile {
+    kind: ElementKind,
+    order: SortOrder,
+    options: SortOptions,
+    trace: RawTrace<Element>,
+}
+
+/// The reason why a trace file was rejected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceError {
+    /// The file is not valid JSON or does not have the expected fields
+    Json(String),
+    /// The file was written by a newer or unknown version of the format
+    Version { found: u32 },
+    /// An input element does not match the element type of the file
+    Element { index: usize, kind: ElementKind },
+    /// An input element would read back differently from the text it is
+    /// shown as, for example a string with a comma in it
+    Text { index: usize, element: String },
+    /// The frames are not numbered in the order of the calls
+    FrameId { found: usize, expected: usize },
+    /// The range of a frame exceeds the array or the range of its parent
+    Range {
+        frame: usize,
+        left: isize,
+        right: isize,
+    },
+    /// The pivot of a frame is not within its range
+    Pivot {
+        frame: usize,
+        pivot: Range<isize>,
+        left: isize,
+        right: isize,
+    },
+    /// The smaller of two pivots is not left of the larger one
+    PivotOrder { frame: usize, pivots: [isize; 2] },
+    /// The index the pivot was chosen from is not within the range of its frame
+    PivotSource {
+        frame: usize,
+        index: isize,
+        left: isize,
+        right: isize,
+    },
+    /// A pruned side of a selection is not within the range of its frame
+    Pruned {
+        frame: usize,
+        pruned: Range<isize>,
+        left: isize,
+        right: isize,
+    },
+    /// The result of a selection is not one of the pivots of its frame
+    SelectResult {
+        frame: usize,
+        index: isize,
+        pivot: Range<isize>,
+    },
+    /// The split point of a merge is not within the range of its frame
+    Middle {
+        frame: usize,
+        middle: isize,
+        left: isize,
+        right: isize,
+    },
+    /// The settled elements of a frame are not within its range
+    Settled {
+        frame: usize,
+        settled: Range<isize>,
+        left: isize,
+        right: isize,
+    },
+    /// The events of a frame are not within the events of its parent
+    Events { frame: usize, events: Range<usize> },
+    /// The work of a frame is not within its events, or a selection did no
+    /// work to place its pivot
+    Work { frame: usize, work: Range<usize> },
+    /// An event belongs to a frame which does not exist
+    EventFrame { event: usize, frame: usize },
+    /// An event refers to an index outside of the array
+    EventIndex {
+        event: usize,
+        index: isize,
+        len: usize,
+    },
+    /// An event pushes a range outside of the array onto the explicit stack
+    StackRange {
+        event: usize,
+        left: isize,
+        right: isize,
+    },
+    /// An event pops a range off an empty explicit stack
+    EmptyStack { event: usize },
+    /// The events of a frame do not begin with its call and end with its
+    /// return
+    FrameBounds { frame: usize },
+    /// An event returns from a call which never began
+    Return { event: usize },
+    /// The amount of calls begun by the events differs from the amount of
+    /// frames
+    Calls { found: usize, expected: usize },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "invalid trace file: {error}"),
+            Self::Version { found } => {
+                write!(f, "unsupported trace version {found}, expected 1 to {VERSION}")
+            }
+            Self::Element { index, kind } => {
+                write!(f, "input element {index} is not of type {kind}")
+            }
+            Self::Text { index, element } => write!(
+                f,
+                "input element {index} (`{element}`) can not be entered as text"
+            ),
+            Self::FrameId { found, expected } => write!(
+                f,
+                "frames must be numbered in call order, found frame {found} where {expected} was expected"
+            ),
+            Self::Range { frame, left, right } => write!(
+                f,
+                "frame {frame}: the range {left}..={right} is outside of the array or its parent"
+            ),
+            Self::Pivot {
+                frame,
+                pivot,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {frame}: the pivot {}..{} is outside of the range {left}..={right}",
+                pivot.start, pivot.end
+            ),
+            Self::PivotOrder {
+                frame,
+                pivots: [low, high],
+            } => write!(
+                f,
+                "frame {frame}: the smaller pivot at {low} is not left of the larger one at {high}"
+            ),
+            Self::PivotSource {
+                frame,
+                index,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {frame}: the pivot was chosen from {index}, outside of the range {left}..={right}"
+            ),
+            Self::Pruned {
+                frame,
+                pruned,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {frame}: the pruned side {}..{} is outside of the range {left}..={right}",
+                pruned.start, pruned.end
+            ),
+            Self::SelectResult {
+                frame,
+                index,
+                pivot,
+            } => write!(
+                f,
+                "frame {frame}: the result {index} is outside of the pivot {}..{}",
+                pivot.start, pivot.end
+            ),
+            Self::Middle {
+                frame,
+                middle,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {frame}: the merge is split at {middle}, outside of the range {left}..={right}"
+            ),
+            Self::Settled {
+                frame,
+                settled,
+                left,
+                right,
+            } => write!(
+                f,
+                "frame {frame}: the settled elements {}..{} are outside of the range {left}..={right}",
+                settled.start, settled.end
+            ),
+            Self::Work { frame, work } => write!(
+                f,
+                "frame {frame}: the work {}..{} is empty or outside of the events of the frame",
+                work.start, work.end
+            ),
+            Self::Events { frame, events } => write!(
+                f,
+                "frame {frame}: the events {}..{} are outside of the events of its parent",
+                events.start, events.end
+            ),
+            Self::EventFrame { event, frame } => {
+                write!(f, "event {event} belongs to frame {frame}, which does not exist")
+            }
+            Self::EventIndex { event, index, len } => write!(
+                f,
+                "event {event}: the index {index} is outside of the array of {len} elements"
+            ),
+            Self::StackRange { event, left, right } => write!(
+                f,
+                "event {event}: the pushed range {left}..={right} is outside of the array"
+            ),
+            Self::EmptyStack { event } => {
+                write!(f, "event {event} pops a range off an empty stack")
+            }
+            Self::FrameBounds { frame } => write!(
+                f,
+                "frame {frame}: its events do not begin with its call and end with its return"
+            ),
+            Self::Return { event } => {
+                write!(f, "event {event} returns from a call which never began")
+            }
+            Self::Calls { found, expected } => {
+                write!(f, "the events begin {found} calls, but there are {expected} frames")
+            }
+        }
+    }
+}
+
+impl TraceFile {
+    pub fn new(
+        kind: ElementKind,
+        order: SortOrder,
+        options: SortOptions,
+        trace: Trace<Element>,
+    ) -> Self {
+        Self {
+            version: VERSION,
+            kind,
+            order,
+            options,
+            trace,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        // every field has a plain JSON representation
+        serde_json::to_string_pretty(self).expect("traces are always serializable")
+    }
+
+    /// Parses and validates a trace file
+    pub fn from_json(json: &str) -> Result<Self, TraceError> {
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        // the version decides how the rest of the file is read
+        let header: Header = parse_json(json)?;
+        let file: RawTraceFile = match header.version {
+            1 | 2 => parse_json::<legacy::TraceFile<legacy::QuickSortFrame>>(json)?.into(),
+            3 => parse_json::<legacy::TraceFile<legacy::NestedFrame>>(json)?.into(),
+            VERSION => parse_json(json)?,
+            found => return Err(TraceError::Version { found }),
+        };
+        let RawTraceFile {
+            kind,
+            order,
+            options,
+            trace:
+                RawTrace {
+                    mut input,
+                    root,
+                    events,
+                },
+        } = file;
+        // JSON does not distinguish `3` from `3.0` or characters from strings
+        for (index, element) in input.iter_mut().enumerate() {
+            *element = element
+                .clone()
+                .coerce(kind)
+                .ok_or(TraceError::Element { index, kind })?;
+            // the input is edited as text, which has to give back the same array
+            let text = element.to_string();
+            if parse_array(&text, kind).as_deref() != Ok(std::slice::from_ref(element)) {
+                return Err(TraceError::Text {
+                    index,
+                    element: text,
+                });
+            }
+        }
+        validate(input.len(), &root, &events)?;
+        let trace = Trace::new(input, root, events);
+        Ok(Self::new(kind, order, options, trace))
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(json: &str) -> Result<T, TraceError> {
+    serde_json::from_str(json).map_err(|error| TraceError::Json(error.to_string()))
+}
+
+/// The formats of older versions, which are converted to the current one
+mod legacy {
+    use std::ops::Range;
+
+    use serde::Deserialize;
+
+    use super::RawTraceFile;
+    use crate::{
+        algorithm::SortOptions,
+        element::{Element, ElementKind, SortOrder},
+        trace::{Event, RawTrace},
+        Frame, FrameKind,
+    };
+
+    #[derive(Deserialize)]
+    pub struct TraceFile<F> {
+        kind: ElementKind,
+        order: SortOrder,
+        options: SortOptions,
+        trace: Trace<F>,
+    }
+
+    #[derive(Deserialize)]
+    struct Trace<F> {
+        input: Vec<Element>,
+        root: F,
+        events: Vec<Event>,
+    }
+
+    impl<F: Into<Frame>> From<TraceFile<F>> for RawTraceFile {
+        fn from(file: TraceFile<F>) -> Self {
+            Self {
+                kind: file.kind,
+                order: file.order,
+                options: file.options,
+                trace: RawTrace {
+                    input: file.trace.input,
+                    root: file.trace.root.into(),
+                    events: file.trace.events,
+                },
+            }
+        }
+    }
+
+    /// A quicksort call of versions 1 and 2
+    ///
+    /// Version 1 also stored a snapshot of the array, which is ignored.
+    #[derive(Deserialize)]
+    pub struct QuickSortFrame {
+        id: usize,
+        left: isize,
+        right: isize,
+        events: Range<usize>,
+        /// The partition, or `None` for base cases
+        full: Option<Partition>,
+    }
+
+    #[derive(Deserialize)]
+    struct Partition {
+        pivot: Range<isize>,
+        pivot_source: isize,
+        /// The index of the event which placed the pivot
+        placed: usize,
+        children: Box<[QuickSortFrame; 2]>,
+    }
+
+    impl From<QuickSortFrame> for Frame {
+        fn from(frame: QuickSortFrame) -> Self {
+            let QuickSortFrame {
+                id,
+                left,
+                right,
+                events,
+                full,
+            } = frame;
+            let label = format!("qS({left}, {right}, ...)");
+            let (work, settled, kind, children) = match full {
+                Some(partition) => (
+                    events.start..partition.placed + 1,
+                    vec![partition.pivot.clone()],
+                    FrameKind::Partition {
+                        pivot: partition.pivot,
+                        pivot_source: partition.pivot_source,
+                    },
+                    Vec::from(*partition.children as [_; 2])
+                        .into_iter()
+                        .map(Frame::from)
+                        .collect(),
+                ),
+                None => (
+                    events.start..events.start + 1,
+                    vec![left..right + 1],
+                    FrameKind::Base,
+                    Vec::new(),
+                ),
+            };
+            Self {
+                id,
+                label,
+                left,
+                right,
+                events,
+                work,
+                settled,
+                kind,
+                children,
+            }
+        }
+    }
+
+    /// A frame of version 3, which nested its children and settled a single
+    /// range
+    #[derive(Deserialize)]
+    pub struct NestedFrame {
+        id: usize,
+        label: String,
+        left: isize,
+        right: isize,
+        events: Range<usize>,
+        work: Range<usize>,
+        settled: Range<isize>,
+        kind: NestedFrameKind,
+        children: Vec<NestedFrame>,
+    }
+
+    impl From<NestedFrame> for Frame {
+        fn from(frame: NestedFrame) -> Self {
+            Self {
+                id: frame.id,
+                label: frame.label,
+                left: frame.left,
+                right: frame.right,
+                events: frame.events,
+                work: frame.work,
+                settled: vec![frame.settled],
+                kind: frame.kind.into(),
+                children: frame.children.into_iter().map(Frame::from).collect(),
+            }
+        }
+    }
+
+    /// The kinds of frames of version 3, which named their fields in snake
+    /// case
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "kebab-case")]
+    pub enum NestedFrameKind {
+        Base,
+        Partition {
+            pivot: Range<isize>,
+            pivot_source: isize,
+        },
+        Merge {
+            middle: isize,
+        },
+        Sequence,
+        Pass,
+    }
+
+    impl From<NestedFrameKind> for FrameKind {
+        fn from(kind: NestedFrameKind) -> Self {
+            match kind {
+                NestedFrameKind::Base => Self::Base,
+                NestedFrameKind::Partition {
+                    pivot,
+                    pivot_source,
+                } => Self::Partition {
+                    pivot,
+                    pivot_source,
+                },
+                NestedFrameKind::Merge { middle } => Self::Merge { middle },
+                NestedFrameKind::Sequence => Self::Sequence,
+                NestedFrameKind::Pass => Self::Pass,
+            }
+        }
+    }
+}
+
+/// Checks a trace of an array of `len` elements
+fn validate(len: usize, root: &Frame, events: &[Event]) -> Result<(), TraceError> {
+    let mut next_id = 0;
+    validate_frame(
+        root,
+        (0, len as isize - 1),
+        0..events.len(),
+        events,
+        &mut next_id,
+    )?;
+    let (mut stack, mut depth, mut calls) = (0usize, 0usize, 0);
+    for (event, step) in events.iter().enumerate() {
+        if step.frame >= next_id {
+            return Err(TraceError::EventFrame {
+                event,
+                frame: step.frame,
+            });
+        }
+        match step.step {
+            Step::PushRange { left, right }
+                if left < 0 || right >= len as isize || right < left - 1 =>
+            {
+                return Err(TraceError::StackRange { event, left, right });
+            }
+            Step::PushRange { .. } => stack += 1,
+            Step::PopRange => {
+                stack = stack
+                    .checked_sub(1)
+                    .ok_or(TraceError::EmptyStack { event })?;
+            }
+            Step::Recurse { .. } => {
+                depth += 1;
+                calls += 1;
+            }
+            Step::Return => {
+                depth = depth.checked_sub(1).ok_or(TraceError::Return { event })?;
+            }
+            _ => {}
+        }
+        let indices = match step.step {
+            Step::ChoosePivot { index } | Step::Compare { index, .. } => vec![index],
+            Step::Swap { a, b } | Step::CompareElements { a, b, .. } => vec![a, b],
+            _ => Vec::new(),
+        };
+        if let Some(&index) = indices
+            .iter()
+            .find(|&&index| index < 0 || index >= len as isize)
+        {
+            return Err(TraceError::EventIndex { event, index, len });
+        }
+    }
+    if calls != next_id {
+        return Err(TraceError::Calls {
+            found: calls,
+            expected: next_id,
+        });
+    }
+    Ok(())
+}
+
+/// Checks `frame` and its children against the range and events of the parent
+fn validate_frame(
+    frame: &Frame,
+    (parent_left, parent_right): (isize, isize),
+    parent_events: Range<usize>,
+    trace_events: &[Event],
+    next_id: &mut usize,
+) -> Result<(), TraceError> {
+    if frame.id != *next_id {
+        return Err(TraceError::FrameId {
+            found: frame.id,
+            expected: *next_id,
+        });
+    }
+    *next_id += 1;
+    let (left, right) = (frame.left, frame.right);
+    // empty ranges may start right after the last element of the parent
+    if left < parent_left || right > parent_right || right < left - 1 {
+        return Err(TraceError::Range {
+            frame: frame.id,
+            left,
+            right,
+        });
+    }
+    let events = &frame.events;
+    if events.start >= events.end
+        || events.start < parent_events.start
+        || events.end > parent_events.end
+    {
+        return Err(TraceError::Events {
+            frame: frame.id,
+            events: events.clone(),
+        });
+    }
+    let (first, last) = (&trace_events[events.start], &trace_events[events.end - 1]);
+    let begins = first.frame == frame.id && first.step == Step::Recurse { left, right };
+    if !begins || last.frame != frame.id || last.step != Step::Return {
+        return Err(TraceError::FrameBounds { frame: frame.id });
+    }
+    let work = &frame.work;
+    if work.start > work.end || work.start < events.start || work.end > events.end {
+        return Err(TraceError::Work {
+            frame: frame.id,
+            work: work.clone(),
+        });
+    }
+    for settled in &frame.settled {
+        if !settled.is_empty() && (settled.start < left || settled.end > right + 1) {
+            return Err(TraceError::Settled {
+                frame: frame.id,
+                settled: settled.clone(),
+                left,
+                right,
+            });
+        }
+    }
+    match frame.kind {
+        FrameKind::Partition {
+            ref pivot,
+            pivot_source,
+        } => validate_pivot(frame, pivot, pivot_source)?,
+        FrameKind::Select {
+            ref pivot,
+            pivot_source,
+            ref pruned,
+            result,
+        } => {
+            validate_pivot(frame, pivot, pivot_source)?;
+            // the pruned sides appear with the last event of the work
+            if frame.work.is_empty() {
+                return Err(TraceError::Work {
+                    frame: frame.id,
+                    work: frame.work.clone(),
+                });
+            }
+            if let Some(pruned) = pruned
+                .iter()
+                .find(|pruned| pruned.is_empty() || pruned.start < left || pruned.end > right + 1)
+            {
+                return Err(TraceError::Pruned {
+                    frame: frame.id,
+                    pruned: pruned.clone(),
+                    left,
+                    right,
+                });
+            }
+            if let Some(index) = result.filter(|index| !pivot.contains(index)) {
+                return Err(TraceError::SelectResult {
+                    frame: frame.id,
+                    index,
+                    pivot: pivot.clone(),
+                });
+            }
+        }
+        FrameKind::DualPartition { pivots } => {
+            if let Some(&pivot) = pivots.iter().find(|pivot| !(left..=right).contains(pivot)) {
+                return Err(TraceError::Pivot {
+                    frame: frame.id,
+                    pivot: pivot..pivot + 1,
+                    left,
+                    right,
+                });
+            }
+            if pivots[0] >= pivots[1] {
+                return Err(TraceError::PivotOrder {
+                    frame: frame.id,
+                    pivots,
+                });
+            }
+        }
+        FrameKind::Merge { middle } if !(left..=right + 1).contains(&middle) => {
+            return Err(TraceError::Middle {
+                frame: frame.id,
+                middle,
+                left,
+                right,
+            });
+        }
+        _ => {}
+    }
+    for child in &frame.children {
+        validate_frame(child, (left, right), events.clone(), trace_events, next_id)?;
+    }
+    Ok(())
+}
+
+/// Checks the pivot of a partitioning `frame` and where it was chosen from
+fn validate_pivot(frame: &Frame, pivot: &Range<isize>, source: isize) -> Result<(), TraceError> {
+    let (left, right) = (frame.left, frame.right);
+    if pivot.start < left || pivot.end > right + 1 || pivot.start > pivot.end {
+        return Err(TraceError::Pivot {
+            frame: frame.id,
+            pivot: pivot.clone(),
+            left,
+            right,
+        });
+    }
+    if !(left..=right).contains(&source) {
+        return Err(TraceError::PivotSource {
+            frame: frame.id,
+            index: source,
+            left,
+            right,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+    use crate::{
+        algorithm::{sort, Algorithm},
+        choice::Choice,
+    };
+
+    fn current(input: &str, algorithm: Algorithm) -> TraceFile {
+        let mut array = parse_array(input, ElementKind::Integer).unwrap();
+        let options = SortOptions {
+            algorithm,
+            ..SortOptions::default()
+        };
+        let trace = sort(&mut array, options, SortOrder::Ascending).unwrap();
+        TraceFile::new(ElementKind::Integer, SortOrder::Ascending, options, trace)
+    }
+
+    #[test]
+    fn migrates_older_versions() {
+        let quick_sort = current("3 1 2", Algorithm::QuickSort);
+        for json in [
+            include_str!("../fixtures/trace-v1.json"),
+            include_str!("../fixtures/trace-v2.json"),
+            include_str!("../fixtures/trace-v3.json"),
+        ] {
+            assert_eq!(TraceFile::from_json(json), Ok(quick_sort.clone()));
+        }
+        let merge_sort =
+            TraceFile::from_json(include_str!("../fixtures/trace-v3-merge-sort.json")).unwrap();
+        let expected = current("2 1 3", Algorithm::MergeSort);
+        assert_eq!(merge_sort.options, expected.options);
+        assert_eq!(merge_sort.trace.events(), expected.trace.events());
+    }
+
+    #[test]
+    fn rejects_selections_without_work() {
+        let mut file = current("3 1 2", Algorithm::QuickSelect);
+        file.trace.root.work = 0..0;
+        assert_eq!(
+            TraceFile::from_json(&file.to_json()),
+            Err(TraceError::Work {
+                frame: 0,
+                work: 0..0
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let json =
+            include_str!("../fixtures/trace-v2.json").replace("\"version\": 2", "\"version\": 5");
+        assert_eq!(
+            TraceFile::from_json(&json),
+            Err(TraceError::Version { found: 5 })
+        );
+    }
+
+    #[test]
+    fn round_trips_every_algorithm() {
+        for &algorithm in Algorithm::ALL {
+            let file = current("3 5 2 7 8 6 1 9 3 4", algorithm);
+            assert_eq!(
+                TraceFile::from_json(&file.to_json()),
+                Ok(file),
+                "{algorithm}"
+            );
+        }
+    }
+
+    /// The error of reading `file` back after `edit` broke it
+    fn rejection(mut file: TraceFile, edit: impl FnOnce(&mut TraceFile)) -> TraceError {
+        edit(&mut file);
+        TraceFile::from_json(&file.to_json()).unwrap_err()
+    }
+
+    /// Rebuilds the trace of `file` with its `event`th event edited
+    fn edit_event(file: &mut TraceFile, event: usize, edit: impl FnOnce(&mut Event)) {
+        let trace = &file.trace;
+        let mut events = trace.events().to_vec();
+        edit(&mut events[event]);
+        file.trace = Trace::new(trace.input.clone(), trace.root.clone(), events);
+    }
+
+    fn step(file: &mut TraceFile, event: usize, step: Step) {
+        edit_event(file, event, |event| event.step = step);
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        assert!(matches!(
+            TraceFile::from_json("{\"version\": 4}"),
+            Err(TraceError::Json(_))
+        ));
+        let file = current("3 1 2", Algorithm::QuickSort);
+        assert_eq!(
+            rejection(file.clone(), |file| file.kind = ElementKind::Char),
+            TraceError::Element {
+                index: 0,
+                kind: ElementKind::Char
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                file.kind = ElementKind::String;
+                file.trace.input[0] = Element::String("a,b".to_string());
+            }),
+            TraceError::Text {
+                index: 0,
+                element: "a,b".to_string()
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| file.trace.root.children[0].id = 2),
+            TraceError::FrameId {
+                found: 2,
+                expected: 1
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| file.trace.root.children[0].left = -1),
+            TraceError::Range {
+                frame: 1,
+                left: -1,
+                right: 0
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                file.trace.root.kind = FrameKind::Partition {
+                    pivot: 3..4,
+                    pivot_source: 2,
+                }
+            }),
+            TraceError::Pivot {
+                frame: 0,
+                pivot: 3..4,
+                left: 0,
+                right: 2
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                file.trace.root.kind = FrameKind::Partition {
+                    pivot: 1..2,
+                    pivot_source: 5,
+                }
+            }),
+            TraceError::PivotSource {
+                frame: 0,
+                index: 5,
+                left: 0,
+                right: 2
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| file.trace.root.settled = vec![0..5]),
+            TraceError::Settled {
+                frame: 0,
+                settled: 0..5,
+                left: 0,
+                right: 2
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                file.trace.root.children[0].events = 8..20;
+            }),
+            TraceError::Events {
+                frame: 1,
+                events: 8..20
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| file.trace.root.work = 0..20),
+            TraceError::Work {
+                frame: 0,
+                work: 0..20
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                edit_event(file, 3, |event| event.frame = 9);
+            }),
+            TraceError::EventFrame { event: 3, frame: 9 }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                let ordering = Ordering::Greater;
+                step(file, 2, Step::Compare { index: 5, ordering });
+            }),
+            TraceError::EventIndex {
+                event: 2,
+                index: 5,
+                len: 3
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                step(file, 5, Step::PushRange { left: 0, right: 5 });
+            }),
+            TraceError::StackRange {
+                event: 5,
+                left: 0,
+                right: 5
+            }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| step(file, 5, Step::PopRange)),
+            TraceError::EmptyStack { event: 5 }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| {
+                step(file, 0, Step::Recurse { left: 0, right: 1 });
+            }),
+            TraceError::FrameBounds { frame: 0 }
+        );
+        assert_eq!(
+            rejection(file.clone(), |file| step(file, 5, Step::Return)),
+            TraceError::Return { event: 12 }
+        );
+        assert_eq!(
+            rejection(file, |file| {
+                step(file, 5, Step::Recurse { left: 0, right: 2 });
+            }),
+            TraceError::Calls {
+                found: 4,
+                expected: 3
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_frames_of_other_algorithms() {
+        let dual_pivot = current("3 1 2", Algorithm::DualPivotQuickSort);
+        assert_eq!(
+            rejection(dual_pivot, |file| {
+                file.trace.root.kind = FrameKind::DualPartition { pivots: [2, 0] };
+            }),
+            TraceError::PivotOrder {
+                frame: 0,
+                pivots: [2, 0]
+            }
+        );
+        let merge_sort = current("3 1 2", Algorithm::MergeSort);
+        assert_eq!(
+            rejection(merge_sort, |file| {
+                file.trace.root.kind = FrameKind::Merge { middle: 7 };
+            }),
+            TraceError::Middle {
+                frame: 0,
+                middle: 7,
+                left: 0,
+                right: 2
+            }
+        );
+        let quick_select = current("3 1 2", Algorithm::QuickSelect);
+        let select = |pruned: Vec<Range<isize>>, result| FrameKind::Select {
+            pivot: 0..1,
+            pivot_source: 2,
+            pruned,
+            result,
+        };
+        assert_eq!(
+            rejection(quick_select.clone(), |file| {
+                file.trace.root.kind = select(vec![0..5], None);
+            }),
+            TraceError::Pruned {
+                frame: 0,
+                pruned: 0..5,
+                left: 0,
+                right: 2
+            }
+        );
+        assert_eq!(
+            rejection(quick_select, |file| {
+                file.trace.root.kind = select(Vec::new(), Some(2));
+            }),
+            TraceError::SelectResult {
+                frame: 0,
+                index: 2,
+                pivot: 0..1
+            }
+        );
+    }
+}
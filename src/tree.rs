@@ -0,0 +1,279 @@
+use std::ops::Range;
+
+use leptos::*;
+use quicksort::{Fallback, Frame, FrameKind};
+
+/// The horizontal space between two neighboring subtrees
+const GAP: f64 = 10.0;
+/// The vertical distance between two levels
+const LEVEL_HEIGHT: f64 = 70.0;
+/// The height of a node
+const NODE_HEIGHT: f64 = 30.0;
+/// The width of a character of a node label
+const LABEL_CHAR_WIDTH: f64 = 7.5;
+
+/// A frame placed in the recursion tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode {
+    pub id: usize,
+    pub label: String,
+    /// The index of the first event of the frame
+    pub start: usize,
+    pub base_case: bool,
+    /// Why introsort switched to another algorithm at this node
+    pub fallback: Option<Fallback>,
+    /// Whether the node stands for a side which a selection does not visit,
+    /// in which case `id` is the frame of the selection
+    pub pruned: bool,
+    /// The horizontal center
+    pub x: f64,
+    pub depth: usize,
+    pub width: f64,
+    /// The index of the parent node
+    pub parent: Option<usize>,
+}
+
+/// A child of a node: either a call or a side pruned by a selection
+enum Branch<'a> {
+    Call(&'a Frame),
+    Pruned(Range<isize>),
+}
+
+/// A subtree during the layout, with nodes relative to its root
+struct Subtree {
+    /// Indices into the node list
+    nodes: Vec<usize>,
+    /// The leftmost and rightmost extent on every level
+    contour: Vec<(f64, f64)>,
+}
+
+/// A tidy layout of the recursion tree
+///
+/// Subtrees are laid out bottom up and pushed apart just as far as their
+/// contours require (Reingold-Tilford), so the shape of the recursion stays
+/// visible: balanced recursions are wide and shallow, bad pivots produce long
+/// one-sided chains.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeLayout {
+    pub nodes: Vec<TreeNode>,
+}
+
+impl TreeLayout {
+    pub fn new(root: &Frame) -> Self {
+        let mut layout = Self { nodes: Vec::new() };
+        let subtree = layout.place(root, 0, None);
+        let min = subtree
+            .contour
+            .iter()
+            .map(|&(left, _)| left)
+            .fold(0.0, f64::min);
+        for node in &mut layout.nodes {
+            node.x -= min;
+        }
+        layout
+    }
+
+    /// The width of a node, which grows with the length of its range and
+    /// fits its label
+    fn node_width(left: isize, right: isize, label: &str) -> f64 {
+        let len = (right - left + 1).max(0) as f64;
+        let label = label.chars().count() as f64 * LABEL_CHAR_WIDTH;
+        (60.0 + len * 8.0).max(label + 10.0)
+    }
+
+    fn place(&mut self, frame: &Frame, depth: usize, parent: Option<usize>) -> Subtree {
+        let index = self.nodes.len();
+        let width = Self::node_width(frame.left, frame.right, &frame.label);
+        self.nodes.push(TreeNode {
+            id: frame.id,
+            label: frame.label.clone(),
+            start: frame.events.start,
+            base_case: frame.kind == FrameKind::Base,
+            fallback: frame.fallback(),
+            pruned: false,
+            x: 0.0,
+            depth,
+            width,
+            parent,
+        });
+        let mut subtree = Subtree {
+            nodes: vec![index],
+            contour: vec![(-width / 2.0, width / 2.0)],
+        };
+        let mut branches: Vec<Branch> = frame.children.iter().map(Branch::Call).collect();
+        if let FrameKind::Select { ref pruned, .. } = frame.kind {
+            for side in pruned {
+                // the lower side goes left of the call on the upper one
+                let at = if side.start == frame.left {
+                    0
+                } else {
+                    branches.len()
+                };
+                branches.insert(at, Branch::Pruned(side.clone()));
+            }
+        }
+        // the children side by side, relative to the first one
+        let mut children: Option<Subtree> = None;
+        let mut last_center = 0.0;
+        for branch in branches {
+            let mut next = match branch {
+                Branch::Call(child) => self.place(child, depth + 1, Some(index)),
+                Branch::Pruned(side) => self.place_pruned(frame, side, depth + 1, index),
+            };
+            let Some(placed) = &mut children else {
+                children = Some(next);
+                continue;
+            };
+            // the smallest offset of the next subtree which keeps it apart
+            let offset = placed
+                .contour
+                .iter()
+                .zip(&next.contour)
+                .map(|(&(_, end), &(start, _))| end - start + GAP)
+                .fold(f64::MIN, f64::max);
+            self.shift(&mut next, offset);
+            last_center = offset;
+            for level in 0..placed.contour.len().max(next.contour.len()) {
+                let extent = match (placed.contour.get(level), next.contour.get(level)) {
+                    (Some(&(start, _)), Some(&(_, end))) => (start, end),
+                    (Some(&extent), None) | (None, Some(&extent)) => extent,
+                    (None, None) => unreachable!(),
+                };
+                match placed.contour.get_mut(level) {
+                    Some(contour) => *contour = extent,
+                    None => placed.contour.push(extent),
+                }
+            }
+            placed.nodes.extend(next.nodes);
+        }
+        let Some(mut children) = children else {
+            return subtree;
+        };
+        // center the parent above its first and last child
+        self.shift(&mut children, -last_center / 2.0);
+        subtree.contour.extend(children.contour);
+        subtree.nodes.extend(children.nodes);
+        subtree
+    }
+
+    /// Places a leaf for the `side` of `frame` which its selection prunes,
+    /// visible once the partition is done
+    fn place_pruned(
+        &mut self,
+        frame: &Frame,
+        side: Range<isize>,
+        depth: usize,
+        parent: usize,
+    ) -> Subtree {
+        let index = self.nodes.len();
+        let (left, right) = (side.start, side.end - 1);
+        let label = format!("pruned({left}, {right})");
+        let width = Self::node_width(left, right, &label);
+        self.nodes.push(TreeNode {
+            id: frame.id,
+            label,
+            start: frame.work.end - 1,
+            base_case: false,
+            fallback: None,
+            pruned: true,
+            x: 0.0,
+            depth,
+            width,
+            parent: Some(parent),
+        });
+        Subtree {
+            nodes: vec![index],
+            contour: vec![(-width / 2.0, width / 2.0)],
+        }
+    }
+
+    fn shift(&mut self, subtree: &mut Subtree, offset: f64) {
+        for &node in &subtree.nodes {
+            self.nodes[node].x += offset;
+        }
+        for (start, end) in &mut subtree.contour {
+            *start += offset;
+            *end += offset;
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.nodes
+            .iter()
+            .map(|node| node.x + node.width / 2.0)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| node.depth + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn y(depth: usize) -> f64 {
+        20.0 + depth as f64 * LEVEL_HEIGHT
+    }
+
+    /// Renders the nodes whose call already happened after `step` events
+    pub fn render(&self, step: usize, active: Option<usize>) -> impl IntoView {
+        let visible = |node: &TreeNode| step > node.start;
+        let edges = self
+            .nodes
+            .iter()
+            .filter(|node| visible(node))
+            .filter_map(|node| {
+                let parent = &self.nodes[node.parent?];
+                Some(view! {
+                    <line
+                        x1=parent.x
+                        y1=(Self::y(parent.depth) + NODE_HEIGHT)
+                        x2=node.x
+                        y2=Self::y(node.depth)
+                        class="edge"
+                        class:pruned=node.pruned
+                    />
+                })
+            })
+            .collect_view();
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|node| visible(node))
+            .map(|node| {
+                let y = Self::y(node.depth);
+                let active = active == Some(node.id) && !node.pruned;
+                view! {
+                    <rect
+                        x=(node.x - node.width / 2.0)
+                        y=y
+                        width=node.width
+                        height=NODE_HEIGHT
+                        rx=5
+                        class="node"
+                        class:base=node.base_case
+                        class:fallback=node.fallback.is_some()
+                        class:pruned=node.pruned
+                        class:active=active
+                    />
+                    <text x=node.x y=(y + NODE_HEIGHT / 2.0) class="node-label anchor-middle">
+                        {node.fallback.map(|fallback| view! { <title>{fallback.reason()}</title> })}
+                        {node.label.clone()}
+                    </text>
+                }
+            })
+            .collect_view();
+        let view_box = format!(
+            "0 0 {} {}",
+            self.width().max(1.0),
+            Self::y(self.depth()) - LEVEL_HEIGHT + NODE_HEIGHT + 20.0
+        );
+        view! {
+            <svg viewBox=view_box>
+                {edges}
+                {nodes}
+            </svg>
+        }
+    }
+}
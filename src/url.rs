@@ -0,0 +1,226 @@
+use std::str::FromStr;
+
+use leptos::{wasm_bindgen::JsValue, window};
+use quicksort::{
+    algorithm::SortOptions,
+    element::{ElementKind, SortOrder},
+};
+
+/// The part of the application state which is stored in the URL fragment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UrlState {
+    /// The array as entered by the user
+    pub input: String,
+    pub kind: ElementKind,
+    pub order: SortOrder,
+    pub options: SortOptions,
+    /// The options of the second run in the comparison view
+    pub compare: Option<SortOptions>,
+    /// The current playback step, or the end if there is none
+    pub step: Option<usize>,
+}
+
+impl UrlState {
+    /// Encodes the state as `key=value` pairs separated by `&`
+    pub fn to_fragment(&self) -> String {
+        let mut fragment = format!(
+            "array={}&type={}&order={}&{}",
+            encode(&self.input),
+            self.kind,
+            self.order,
+            format_options(&self.options, "")
+        );
+        if let Some(compare) = &self.compare {
+            fragment += &format!("&{}", format_options(compare, "compare-"));
+        }
+        if let Some(step) = self.step {
+            fragment += &format!("&step={step}");
+        }
+        fragment
+    }
+
+    /// Decodes a fragment created by [`UrlState::to_fragment`]
+    ///
+    /// Missing or invalid values fall back to `default`.
+    pub fn from_fragment(fragment: &str, default: &Self) -> Self {
+        let mut state = default.clone();
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        for (key, value) in fragment.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "array" => {
+                    if let Some(input) = decode(value) {
+                        state.input = input;
+                    }
+                }
+                "type" => {
+                    set(&mut state.kind, value);
+                }
+                "order" => {
+                    set(&mut state.order, value);
+                }
+                "step" => {
+                    if let Ok(step) = value.parse() {
+                        state.step = Some(step);
+                    }
+                }
+                _ => match key.strip_prefix("compare-") {
+                    // the comparison only starts with its first valid option
+                    Some(key) => {
+                        let mut compare = state.compare.unwrap_or_default();
+                        if apply(&mut compare, key, value) {
+                            state.compare = Some(compare);
+                        }
+                    }
+                    None => {
+                        apply(&mut state.options, key, value);
+                    }
+                },
+            }
+        }
+        state
+    }
+}
+
+/// Encodes the options as `key=value` pairs with every key starting with
+/// `prefix`
+fn format_options(options: &SortOptions, prefix: &str) -> String {
+    format!(
+        "{prefix}algorithm={}&{prefix}scheme={}&{prefix}pivot={}&{prefix}seed={}&{prefix}depth-limit={}&{prefix}cutoff={}&{prefix}smaller-first={}&{prefix}k={}",
+        options.algorithm,
+        options.scheme,
+        options.pivot,
+        options.seed,
+        options.depth_limit,
+        options.cutoff,
+        options.smaller_first,
+        options.k
+    )
+}
+
+/// Sets the option named by `key` to `value`
+///
+/// Returns whether `key` names an option and `value` is valid for it.
+fn apply(options: &mut SortOptions, key: &str, value: &str) -> bool {
+    match key {
+        "algorithm" => set(&mut options.algorithm, value),
+        "scheme" => set(&mut options.scheme, value),
+        "pivot" => set(&mut options.pivot, value),
+        "seed" => set(&mut options.seed, value),
+        "depth-limit" => set(&mut options.depth_limit, value),
+        "cutoff" => set(&mut options.cutoff, value),
+        "smaller-first" => set(&mut options.smaller_first, value),
+        "k" => set(&mut options.k, value),
+        _ => false,
+    }
+}
+
+/// Parses `value` into `field`, which is left unchanged if it is invalid
+fn set<T: FromStr>(field: &mut T, value: &str) -> bool {
+    value.parse().map(|value| *field = value).is_ok()
+}
+
+/// Percent-encodes everything except unreserved characters and commas
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b',' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded += &format!("%{byte:02X}"),
+        }
+    }
+    encoded
+}
+
+fn decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Reads the fragment of the current URL
+pub fn read_fragment() -> String {
+    window().location().hash().unwrap_or_default()
+}
+
+/// Replaces the fragment of the current URL without adding a history entry
+pub fn write_fragment(fragment: &str) {
+    if let Ok(history) = window().history() {
+        _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&format!("#{fragment}")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quicksort::algorithm::Algorithm;
+
+    use super::*;
+
+    fn default() -> UrlState {
+        UrlState {
+            input: "3, 1, 2".to_string(),
+            kind: ElementKind::Integer,
+            order: SortOrder::Ascending,
+            options: SortOptions::default(),
+            compare: None,
+            step: None,
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let state = UrlState {
+            input: "grüße, 😀, 100%, a&b=c, #x, +".to_string(),
+            kind: ElementKind::String,
+            order: SortOrder::Descending,
+            options: SortOptions {
+                algorithm: Algorithm::IntroSort,
+                seed: 7,
+                depth_limit: 3,
+                smaller_first: false,
+                ..SortOptions::default()
+            },
+            compare: Some(SortOptions {
+                algorithm: Algorithm::QuickSelect,
+                k: 2,
+                ..SortOptions::default()
+            }),
+            step: Some(12),
+        };
+        let fragment = state.to_fragment();
+        assert!(fragment.is_ascii(), "{fragment}");
+        assert_eq!(UrlState::from_fragment(&fragment, &default()), state);
+        let hashed = format!("#{fragment}");
+        assert_eq!(UrlState::from_fragment(&hashed, &default()), state);
+    }
+
+    #[test]
+    fn ignores_broken_escapes() {
+        for input in ["%", "%4", "a%4", "%G1", "%FF"] {
+            let state = UrlState::from_fragment(&format!("array={input}&step=3"), &default());
+            assert_eq!(state.input, default().input, "{input}");
+            assert_eq!(state.step, Some(3), "{input}");
+        }
+    }
+
+    #[test]
+    fn reads_the_compare_keys() {
+        let state = UrlState::from_fragment("compare-k=2&compare-cutoff=8&k=1", &default());
+        let compare = state.compare.unwrap();
+        assert_eq!((compare.k, compare.cutoff, state.options.k), (2, 8, 1));
+        // unknown keys and invalid values do not start a comparison
+        let state = UrlState::from_fragment("compare-foo=1&compare-k=x", &default());
+        assert_eq!(state.compare, None);
+    }
+}